@@ -1,4 +1,7 @@
-use crate::TaskInfo;
+use crate::{
+    SwitchLatency, SwitchTraceDump, TaskDumpEntry, TaskInfo, TaskInfoSummary, TaskStats,
+    TaskStatus, TimeBreakdown, TraceDump, MAX_SYSCALL_NUM,
+};
 
 use super::{Stat, TimeVal};
 
@@ -26,7 +29,61 @@ pub const SYSCALL_MAIL_READ: usize = 401;
 pub const SYSCALL_MAIL_WRITE: usize = 402;
 pub const SYSCALL_DUP: usize = 24;
 pub const SYSCALL_PIPE: usize = 59;
+pub const SYSCALL_GET_TIME_ANOMALIES: usize = 403;
+pub const SYSCALL_SLEEP_UNTIL: usize = 404;
+pub const SYSCALL_PREDICT_SCHEDULE: usize = 405;
+pub const SYSCALL_SET_AUTO_FREEZE_THRESHOLD: usize = 406;
+pub const SYSCALL_TASK_STATS: usize = 407;
 pub const SYSCALL_TASK_INFO: usize = 410;
+pub const SYSCALL_SWAP_PRIORITIES: usize = 411;
+pub const SYSCALL_GET_QUANTUM_TIME: usize = 412;
+pub const SYSCALL_SET_PANIC_POLICY: usize = 413;
+pub const SYSCALL_SNAPSHOT_SYSCALLS: usize = 414;
+pub const SYSCALL_SET_TASK_NAME: usize = 415;
+pub const SYSCALL_EXPORT_TASKS_CSV: usize = 416;
+pub const SYSCALL_GET_REMAINING_SLICE: usize = 417;
+pub const SYSCALL_GET_LAST_EXITED: usize = 418;
+pub const SYSCALL_BARRIER_INIT: usize = 419;
+pub const SYSCALL_BARRIER_WAIT: usize = 420;
+pub const SYSCALL_SET_TRACE_ENABLED: usize = 421;
+pub const SYSCALL_DUMP_TRACE: usize = 422;
+pub const SYSCALL_GET_RUN_TIME_STDDEV: usize = 423;
+pub const SYSCALL_SET_FALLBACK_TASK: usize = 424;
+pub const SYSCALL_GET_TASK_BRIEF: usize = 425;
+pub const SYSCALL_SET_SYSCALL_VALIDATION: usize = 426;
+pub const SYSCALL_SET_ACCOUNT_SELF_OVERHEAD: usize = 427;
+pub const SYSCALL_GET_RECENT_SWITCH_LATENCY: usize = 428;
+pub const SYSCALL_SWITCH_TO: usize = 429;
+pub const SYSCALL_GET_INIT_DURATION: usize = 430;
+pub const SYSCALL_PEEK_NEXT_TASK: usize = 431;
+pub const SYSCALL_RECORD_SCHEDULE: usize = 432;
+pub const SYSCALL_STOP_RECORDING: usize = 433;
+pub const SYSCALL_REPLAY_SCHEDULE: usize = 434;
+pub const SYSCALL_IS_SYSTEM_IDLE: usize = 435;
+pub const SYSCALL_SET_MAX_SWITCHES: usize = 436;
+pub const SYSCALL_GET_PRIORITY_INVERSIONS: usize = 437;
+pub const SYSCALL_SET_MIN_SHARE: usize = 438;
+pub const SYSCALL_DUMP_TASK_TABLE: usize = 439;
+pub const SYSCALL_GET_SYSTEM_SYSCALL_TIMES: usize = 440;
+pub const SYSCALL_UPTIME_TICKS: usize = 441;
+pub const SYSCALL_DUMP_SWITCH_TRACE: usize = 442;
+pub const SYSCALL_RESET_SYSCALL_STATS: usize = 443;
+pub const SYSCALL_GET_IDLE_TIME: usize = 444;
+pub const SYSCALL_TIME_SINCE: usize = 445;
+pub const SYSCALL_SET_CPU_BUDGET: usize = 446;
+pub const SYSCALL_GET_DISPATCH_COVERAGE: usize = 447;
+pub const SYSCALL_SET_TIME_SLICE: usize = 448;
+pub const SYSCALL_RESTART_TASK: usize = 449;
+pub const SYSCALL_GET_PREV_RUN_INFO: usize = 450;
+pub const SYSCALL_WAITTASK: usize = 451;
+pub const SYSCALL_LIST_TASKS_BY_STATUS: usize = 452;
+pub const SYSCALL_SLEEP_US: usize = 453;
+pub const SYSCALL_PRIORITY_BOOST_BEGIN: usize = 454;
+pub const SYSCALL_PRIORITY_BOOST_END: usize = 455;
+pub const SYSCALL_GET_LRU_TASK: usize = 456;
+pub const SYSCALL_GET_SCHED_OVERHEAD: usize = 457;
+pub const SYSCALL_STATE_FINGERPRINT: usize = 458;
+pub const SYSCALL_SET_DEADLINE: usize = 459;
 pub const SYSCALL_THREAD_CREATE: usize = 460;
 pub const SYSCALL_WAITTID: usize = 462;
 pub const SYSCALL_MUTEX_CREATE: usize = 463;
@@ -39,6 +96,32 @@ pub const SYSCALL_SEMAPHORE_DOWN: usize = 470;
 pub const SYSCALL_CONDVAR_CREATE: usize = 471;
 pub const SYSCALL_CONDVAR_SIGNAL: usize = 472;
 pub const SYSCALL_CONDVAR_WAIT: usize = 473;
+pub const SYSCALL_TOTAL_SYSTEM_SYSCALLS: usize = 474;
+pub const SYSCALL_SOFT_RESET: usize = 475;
+pub const SYSCALL_GET_SLOT_IDLE: usize = 476;
+pub const SYSCALL_GET_DISPATCH_BALANCE: usize = 477;
+pub const SYSCALL_NEXT_WAKE_TIME: usize = 478;
+pub const SYSCALL_GET_KERNEL_ENTRIES: usize = 479;
+pub const SYSCALL_GET_TASK_RECORD: usize = 480;
+pub const SYSCALL_SET_SYSCALL_STORM_THRESHOLD: usize = 481;
+pub const SYSCALL_LIST_UNINIT_SLOTS: usize = 482;
+pub const SYSCALL_STACK_HIGH_WATER: usize = 483;
+pub const SYSCALL_SET_COUNT_FAILURES: usize = 484;
+pub const SYSCALL_LIST_RELATED: usize = 485;
+pub const SYSCALL_SET_KERNEL_RESIDENCY_WARN: usize = 486;
+pub const SYSCALL_GET_BUSIEST_TASK: usize = 487;
+pub const SYSCALL_TASK_INFO_BY_ID: usize = 488;
+pub const SYSCALL_SET_PREEMPTION: usize = 489;
+pub const SYSCALL_EXTEND_BUDGET: usize = 490;
+pub const SYSCALL_GET_TIME_BREAKDOWN: usize = 491;
+pub const SYSCALL_SET_COUNTING: usize = 492;
+pub const SYSCALL_GET_CPU_GINI: usize = 493;
+pub const SYSCALL_GET_SELECTION_STATS: usize = 494;
+pub const SYSCALL_GET_TASK_STATUSES: usize = 495;
+pub const SYSCALL_GET_MAX_IDLE_STRETCH: usize = 496;
+pub const SYSCALL_INJECT_FAULT: usize = 497;
+pub const SYSCALL_SET_GLOBAL_QUANTUM: usize = 498;
+pub const SYSCALL_GET_GLOBAL_QUANTUM: usize = 499;
 
 pub fn syscall(id: usize, args: [usize; 3]) -> isize {
     let mut ret: isize;
@@ -205,6 +288,372 @@ pub fn sys_task_info(info: &TaskInfo) -> isize {
     syscall(SYSCALL_TASK_INFO, [info as *const _ as usize, 0, 0])
 }
 
+pub fn sys_swap_priorities(id_a: usize, id_b: usize) -> isize {
+    syscall(SYSCALL_SWAP_PRIORITIES, [id_a, id_b, 0])
+}
+
+pub fn sys_get_quantum_time() -> isize {
+    syscall(SYSCALL_GET_QUANTUM_TIME, [0, 0, 0])
+}
+
+pub fn sys_set_panic_policy(policy: usize) -> isize {
+    syscall(SYSCALL_SET_PANIC_POLICY, [policy, 0, 0])
+}
+
+pub fn sys_snapshot_syscalls(snapshot: &mut [u32; MAX_SYSCALL_NUM]) -> isize {
+    syscall(
+        SYSCALL_SNAPSHOT_SYSCALLS,
+        [snapshot.as_mut_ptr() as usize, 0, 0],
+    )
+}
+
+pub fn sys_set_task_name(name: &str) -> isize {
+    syscall(
+        SYSCALL_SET_TASK_NAME,
+        [name.as_ptr() as usize, name.len(), 0],
+    )
+}
+
+pub fn sys_export_tasks_csv(buffer: &mut [u8]) -> isize {
+    syscall(
+        SYSCALL_EXPORT_TASKS_CSV,
+        [buffer.as_mut_ptr() as usize, buffer.len(), 0],
+    )
+}
+
+pub fn sys_get_remaining_slice() -> isize {
+    syscall(SYSCALL_GET_REMAINING_SLICE, [0, 0, 0])
+}
+
+pub fn sys_get_last_exited(time_us: &mut usize) -> isize {
+    syscall(SYSCALL_GET_LAST_EXITED, [time_us as *mut _ as usize, 0, 0])
+}
+
+pub fn sys_barrier_init(id: usize, count: usize) -> isize {
+    syscall(SYSCALL_BARRIER_INIT, [id, count, 0])
+}
+
+pub fn sys_barrier_wait(id: usize) -> isize {
+    syscall(SYSCALL_BARRIER_WAIT, [id, 0, 0])
+}
+
+pub fn sys_set_trace_enabled(id: usize, enabled: bool) -> isize {
+    syscall(SYSCALL_SET_TRACE_ENABLED, [id, enabled as usize, 0])
+}
+
+pub fn sys_dump_trace(id: usize, dump: &mut TraceDump) -> isize {
+    syscall(SYSCALL_DUMP_TRACE, [id, dump as *mut _ as usize, 0])
+}
+
+pub fn sys_get_run_time_stddev() -> isize {
+    syscall(SYSCALL_GET_RUN_TIME_STDDEV, [0, 0, 0])
+}
+
+pub fn sys_set_fallback_task(id: usize) -> isize {
+    syscall(SYSCALL_SET_FALLBACK_TASK, [id, 0, 0])
+}
+
+pub fn sys_get_task_brief() -> isize {
+    syscall(SYSCALL_GET_TASK_BRIEF, [0, 0, 0])
+}
+
+pub fn sys_set_syscall_validation(enabled: bool) -> isize {
+    syscall(SYSCALL_SET_SYSCALL_VALIDATION, [enabled as usize, 0, 0])
+}
+
+pub fn sys_set_account_self_overhead(enabled: bool) -> isize {
+    syscall(SYSCALL_SET_ACCOUNT_SELF_OVERHEAD, [enabled as usize, 0, 0])
+}
+
+pub fn sys_get_recent_switch_latency(latency: &mut SwitchLatency) -> isize {
+    syscall(
+        SYSCALL_GET_RECENT_SWITCH_LATENCY,
+        [latency as *mut _ as usize, 0, 0],
+    )
+}
+
+pub fn sys_switch_to(id: usize) -> isize {
+    syscall(SYSCALL_SWITCH_TO, [id, 0, 0])
+}
+
+pub fn sys_get_init_duration() -> isize {
+    syscall(SYSCALL_GET_INIT_DURATION, [0, 0, 0])
+}
+
+pub fn sys_peek_next_task(current: &mut usize) -> isize {
+    syscall(SYSCALL_PEEK_NEXT_TASK, [current as *mut _ as usize, 0, 0])
+}
+
+pub fn sys_record_schedule() -> isize {
+    syscall(SYSCALL_RECORD_SCHEDULE, [0, 0, 0])
+}
+
+pub fn sys_stop_recording(buf: &mut [usize]) -> isize {
+    syscall(
+        SYSCALL_STOP_RECORDING,
+        [buf.as_mut_ptr() as usize, buf.len(), 0],
+    )
+}
+
+pub fn sys_replay_schedule(schedule: &[usize]) -> isize {
+    syscall(
+        SYSCALL_REPLAY_SCHEDULE,
+        [schedule.as_ptr() as usize, schedule.len(), 0],
+    )
+}
+
+pub fn sys_is_system_idle() -> isize {
+    syscall(SYSCALL_IS_SYSTEM_IDLE, [0, 0, 0])
+}
+
+pub fn sys_set_max_switches(n: usize) -> isize {
+    syscall(SYSCALL_SET_MAX_SWITCHES, [n, 0, 0])
+}
+
+pub fn sys_get_priority_inversions() -> isize {
+    syscall(SYSCALL_GET_PRIORITY_INVERSIONS, [0, 0, 0])
+}
+
+pub fn sys_set_min_share(id: usize, permille: usize) -> isize {
+    syscall(SYSCALL_SET_MIN_SHARE, [id, permille, 0])
+}
+
+pub fn sys_dump_task_table(buf: &mut [TaskDumpEntry]) -> isize {
+    syscall(
+        SYSCALL_DUMP_TASK_TABLE,
+        [buf.as_mut_ptr() as usize, buf.len(), 0],
+    )
+}
+
+pub fn sys_get_system_syscall_times(buf: &mut [u64; MAX_SYSCALL_NUM]) -> isize {
+    syscall(
+        SYSCALL_GET_SYSTEM_SYSCALL_TIMES,
+        [buf as *mut _ as usize, 0, 0],
+    )
+}
+
+pub fn sys_uptime_ticks() -> isize {
+    syscall(SYSCALL_UPTIME_TICKS, [0, 0, 0])
+}
+
+pub fn sys_dump_switch_trace(dump: &mut SwitchTraceDump) -> isize {
+    syscall(SYSCALL_DUMP_SWITCH_TRACE, [dump as *mut _ as usize, 0, 0])
+}
+
+pub fn sys_reset_syscall_stats() -> isize {
+    syscall(SYSCALL_RESET_SYSCALL_STATS, [0, 0, 0])
+}
+
+pub fn sys_get_idle_time() -> isize {
+    syscall(SYSCALL_GET_IDLE_TIME, [0, 0, 0])
+}
+
+pub fn sys_time_since(epoch_us: usize) -> isize {
+    syscall(SYSCALL_TIME_SINCE, [epoch_us, 0, 0])
+}
+
+pub fn sys_set_cpu_budget(id: usize, us: usize) -> isize {
+    syscall(SYSCALL_SET_CPU_BUDGET, [id, us, 0])
+}
+
+pub fn sys_get_dispatch_coverage(total: *mut usize) -> isize {
+    syscall(SYSCALL_GET_DISPATCH_COVERAGE, [total as usize, 0, 0])
+}
+
+pub fn sys_set_time_slice(id: usize, ms: usize) -> isize {
+    syscall(SYSCALL_SET_TIME_SLICE, [id, ms, 0])
+}
+
+pub fn sys_restart_task(id: usize) -> isize {
+    syscall(SYSCALL_RESTART_TASK, [id, 0, 0])
+}
+
+pub fn sys_get_prev_run_info(id: usize, info: *mut TaskInfoSummary) -> isize {
+    syscall(SYSCALL_GET_PREV_RUN_INFO, [id, info as usize, 0])
+}
+
+pub fn sys_waittask(id: usize, timeout_ms: usize) -> isize {
+    syscall(SYSCALL_WAITTASK, [id, timeout_ms, 0])
+}
+
+pub fn sys_list_tasks_by_status(status: TaskStatus, buf: &mut [usize]) -> isize {
+    syscall(
+        SYSCALL_LIST_TASKS_BY_STATUS,
+        [status as usize, buf.as_mut_ptr() as usize, buf.len()],
+    )
+}
+
+pub fn sys_sleep_us(us: usize) -> isize {
+    syscall(SYSCALL_SLEEP_US, [us, 0, 0])
+}
+
+pub fn sys_priority_boost_begin() -> isize {
+    syscall(SYSCALL_PRIORITY_BOOST_BEGIN, [0, 0, 0])
+}
+
+pub fn sys_priority_boost_end() -> isize {
+    syscall(SYSCALL_PRIORITY_BOOST_END, [0, 0, 0])
+}
+
+pub fn sys_get_lru_task() -> isize {
+    syscall(SYSCALL_GET_LRU_TASK, [0, 0, 0])
+}
+
+pub fn sys_get_sched_overhead() -> isize {
+    syscall(SYSCALL_GET_SCHED_OVERHEAD, [0, 0, 0])
+}
+
+pub fn sys_state_fingerprint() -> isize {
+    syscall(SYSCALL_STATE_FINGERPRINT, [0, 0, 0])
+}
+
+pub fn sys_set_deadline(ms: usize) -> isize {
+    syscall(SYSCALL_SET_DEADLINE, [ms, 0, 0])
+}
+
+pub fn sys_total_system_syscalls() -> isize {
+    syscall(SYSCALL_TOTAL_SYSTEM_SYSCALLS, [0, 0, 0])
+}
+
+pub fn sys_soft_reset() -> ! {
+    syscall(SYSCALL_SOFT_RESET, [0, 0, 0]);
+    panic!("sys_soft_reset never returns!");
+}
+
+pub fn sys_get_slot_idle(id: usize) -> isize {
+    syscall(SYSCALL_GET_SLOT_IDLE, [id, 0, 0])
+}
+
+pub fn sys_get_dispatch_balance() -> isize {
+    syscall(SYSCALL_GET_DISPATCH_BALANCE, [0, 0, 0])
+}
+
+pub fn sys_next_wake_time() -> isize {
+    syscall(SYSCALL_NEXT_WAKE_TIME, [0, 0, 0])
+}
+
+pub fn sys_get_kernel_entries() -> isize {
+    syscall(SYSCALL_GET_KERNEL_ENTRIES, [0, 0, 0])
+}
+
+pub fn sys_get_task_record(id: usize, buffer: &mut [u8]) -> isize {
+    syscall(
+        SYSCALL_GET_TASK_RECORD,
+        [id, buffer.as_mut_ptr() as usize, buffer.len()],
+    )
+}
+
+pub fn sys_set_syscall_storm_threshold(per_sec: usize) -> isize {
+    syscall(SYSCALL_SET_SYSCALL_STORM_THRESHOLD, [per_sec, 0, 0])
+}
+
+pub fn sys_list_uninit_slots(buf: &mut [usize]) -> isize {
+    syscall(
+        SYSCALL_LIST_UNINIT_SLOTS,
+        [buf.as_mut_ptr() as usize, buf.len(), 0],
+    )
+}
+
+pub fn sys_stack_high_water(id: usize) -> isize {
+    syscall(SYSCALL_STACK_HIGH_WATER, [id, 0, 0])
+}
+
+pub fn sys_set_count_failures(enabled: bool) -> isize {
+    syscall(SYSCALL_SET_COUNT_FAILURES, [enabled as usize, 0, 0])
+}
+
+pub fn sys_list_related(id: usize, buf: &mut [usize]) -> isize {
+    syscall(
+        SYSCALL_LIST_RELATED,
+        [id, buf.as_mut_ptr() as usize, buf.len()],
+    )
+}
+
+pub fn sys_set_kernel_residency_warn(us: usize) -> isize {
+    syscall(SYSCALL_SET_KERNEL_RESIDENCY_WARN, [us, 0, 0])
+}
+
+pub fn sys_get_busiest_task() -> isize {
+    syscall(SYSCALL_GET_BUSIEST_TASK, [0, 0, 0])
+}
+
+pub fn sys_task_info_by_id(id: usize, info: &TaskInfo) -> isize {
+    syscall(SYSCALL_TASK_INFO_BY_ID, [id, info as *const _ as usize, 0])
+}
+
+pub fn sys_set_preemption(enabled: bool) -> isize {
+    syscall(SYSCALL_SET_PREEMPTION, [enabled as usize, 0, 0])
+}
+
+pub fn sys_extend_budget(id: usize, ms: usize) -> isize {
+    syscall(SYSCALL_EXTEND_BUDGET, [id, ms, 0])
+}
+
+pub fn sys_get_time_breakdown(tb: &mut TimeBreakdown) -> isize {
+    syscall(SYSCALL_GET_TIME_BREAKDOWN, [tb as *mut _ as usize, 0, 0])
+}
+
+pub fn sys_set_counting(enabled: bool) -> isize {
+    syscall(SYSCALL_SET_COUNTING, [enabled as usize, 0, 0])
+}
+
+pub fn sys_get_cpu_gini() -> isize {
+    syscall(SYSCALL_GET_CPU_GINI, [0, 0, 0])
+}
+
+pub fn sys_get_selection_stats(calls: &mut usize) -> isize {
+    syscall(
+        SYSCALL_GET_SELECTION_STATS,
+        [calls as *mut _ as usize, 0, 0],
+    )
+}
+
+pub fn sys_get_task_statuses(buf: &mut [u8]) -> isize {
+    syscall(
+        SYSCALL_GET_TASK_STATUSES,
+        [buf.as_mut_ptr() as usize, buf.len(), 0],
+    )
+}
+
+pub fn sys_get_max_idle_stretch() -> isize {
+    syscall(SYSCALL_GET_MAX_IDLE_STRETCH, [0, 0, 0])
+}
+
+pub fn sys_inject_fault(kind: usize) -> isize {
+    syscall(SYSCALL_INJECT_FAULT, [kind, 0, 0])
+}
+
+pub fn sys_set_global_quantum(ms: usize) -> isize {
+    syscall(SYSCALL_SET_GLOBAL_QUANTUM, [ms, 0, 0])
+}
+
+pub fn sys_get_global_quantum() -> isize {
+    syscall(SYSCALL_GET_GLOBAL_QUANTUM, [0, 0, 0])
+}
+
+pub fn sys_get_time_anomalies() -> isize {
+    syscall(SYSCALL_GET_TIME_ANOMALIES, [0, 0, 0])
+}
+
+pub fn sys_sleep_until(abs_us: usize) -> isize {
+    syscall(SYSCALL_SLEEP_UNTIL, [abs_us, 0, 0])
+}
+
+pub fn sys_predict_schedule(buf: &mut [usize]) -> isize {
+    syscall(
+        SYSCALL_PREDICT_SCHEDULE,
+        [buf.as_mut_ptr() as usize, buf.len(), 0],
+    )
+}
+
+pub fn sys_set_auto_freeze_threshold(ms: usize) -> isize {
+    syscall(SYSCALL_SET_AUTO_FREEZE_THRESHOLD, [ms, 0, 0])
+}
+
+pub fn sys_task_stats(id: usize, stats: &TaskStats) -> isize {
+    syscall(SYSCALL_TASK_STATS, [id, stats as *const _ as usize, 0])
+}
+
 pub fn sys_thread_create(entry: usize, arg: usize) -> isize {
     syscall(SYSCALL_THREAD_CREATE, [entry, arg, 0])
 }
@@ -8,3 +8,24 @@ pub const APP_BASE_ADDRESS: usize = 0x80400000;
 pub const APP_SIZE_LIMIT: usize = 0x20000;
 pub const CLOCK_FREQ: usize = 12500000;
 pub const MAX_SYSCALL_NUM: usize = 500;
+pub const MIN_PRIORITY: usize = 2;
+pub const MAX_PRIORITY: usize = 64;
+pub const DEFAULT_PRIORITY: usize = 16;
+pub const PRIORITY_CEILING: usize = 64;
+pub const DEFAULT_WEIGHT: usize = 1;
+pub const TASK_NAME_LEN: usize = 16;
+pub const DEFAULT_TIME_SLICE_MS: usize = 10;
+pub const MIN_TIME_SLICE_MS: usize = 1;
+pub const TRACE_BUF_LEN: usize = 64;
+pub const SWITCH_LATENCY_WINDOW: usize = 16;
+pub const SWITCH_TRACE_LEN: usize = 64;
+pub const STUCK_SELECTION_THRESHOLD: usize = 64;
+pub const TRAP_CAUSE_NUM: usize = 5;
+pub const SYSCALL_RATE_WINDOW_MS: usize = 100;
+pub const SYSCALL_RATE_SAMPLES: usize = 64;
+pub const SYSCALL_STORM_PER_SEC: usize = 50000;
+pub const STACK_SENTINEL: u8 = 0xa5;
+pub const INTERACTIVE_YIELD_PERCENT: usize = 50;
+pub const INTERACTIVE_IO_PERCENT: usize = 50;
+pub const MAX_BARRIERS: usize = 16;
+pub const MAX_SCHEDULE_LEN: usize = 256;
@@ -1,7 +1,63 @@
 //! The panic handler
 
-use crate::sbi::shutdown;
+use crate::sbi::{reboot, shutdown, system_shutdown};
+use crate::sync::UPSafeCell;
 use core::panic::PanicInfo;
+use lazy_static::*;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+/// what the kernel does once it stops, either because all applications
+/// completed or because it panicked
+pub enum PanicPolicy {
+    /// shut down through the legacy SBI call (the default)
+    Panic,
+    /// spin on `wfi` forever, leaving the machine inspectable
+    Halt,
+    /// ask SBI for a cold reboot
+    Reboot,
+    /// ask SBI for a shutdown which reports whether the kernel failed
+    Shutdown,
+}
+
+lazy_static! {
+    /// policy applied by [`finish`] and the panic handler
+    static ref PANIC_POLICY: UPSafeCell<PanicPolicy> =
+        unsafe { UPSafeCell::new(PanicPolicy::Panic) };
+}
+
+/// set what the kernel does when it stops
+pub fn set_panic_policy(policy: PanicPolicy) {
+    *PANIC_POLICY.exclusive_access() = policy;
+}
+
+/// stop the machine according to `policy`; `failed` tells a clean completion from a crash
+fn stop(policy: PanicPolicy, failed: bool) -> ! {
+    match policy {
+        PanicPolicy::Panic => shutdown(),
+        PanicPolicy::Halt => loop {
+            unsafe { riscv::asm::wfi() }
+        },
+        PanicPolicy::Reboot => reboot(),
+        PanicPolicy::Shutdown => system_shutdown(failed),
+    }
+}
+
+/// Stop the kernel because there is nothing left to run.
+///
+/// Prints `msg` and stops without reporting a failure, under the default
+/// [`PanicPolicy::Panic`] through the plain `shutdown` without panicking.
+pub fn finish(msg: &str) -> ! {
+    println!("[kernel] {}", msg);
+    stop(*PANIC_POLICY.exclusive_access(), false)
+}
+
+/// Stop the kernel because the tasks left can never make progress.
+///
+/// Like [`finish`], but reports a failure to policies that can tell.
+pub fn fail(msg: &str) -> ! {
+    println!("[kernel] {}", msg);
+    stop(*PANIC_POLICY.exclusive_access(), true)
+}
 
 #[panic_handler]
 /// panic handler
@@ -16,5 +72,5 @@ fn panic(info: &PanicInfo) -> ! {
     } else {
         println!("[kernel] Panicked: {}", info.message().unwrap());
     }
-    shutdown()
+    stop(*PANIC_POLICY.exclusive_access(), true)
 }
@@ -6,6 +6,7 @@
 //! [`KernelStack`] and [`UserStack`].
 
 use crate::config::*;
+use crate::task::AppDescriptor;
 use crate::trap::TrapContext;
 
 #[repr(align(4096))]
@@ -22,6 +23,12 @@ struct UserStack {
     data: [u8; USER_STACK_SIZE],
 }
 
+/// per-app configuration, indexed by app id
+///
+/// `link_app.S` carries no metadata yet, so apps past the end of this table
+/// run with [`AppDescriptor::default`].
+static APP_DESCRIPTORS: &[AppDescriptor] = &[];
+
 /// kernel stack instance
 static KERNEL_STACK: [KernelStack; MAX_APP_NUM] = [KernelStack {
     data: [0; KERNEL_STACK_SIZE],
@@ -36,6 +43,22 @@ impl KernelStack {
     fn get_sp(&self) -> usize {
         self.data.as_ptr() as usize + KERNEL_STACK_SIZE
     }
+    /// Overwrite the whole stack with [`STACK_SENTINEL`].
+    fn fill_sentinel(&self) {
+        let bottom = self.data.as_ptr() as *mut u8;
+        (0..KERNEL_STACK_SIZE)
+            .for_each(|i| unsafe { bottom.add(i).write_volatile(STACK_SENTINEL) });
+    }
+    /// Bytes from the top down to the lowest byte that no longer holds the sentinel.
+    ///
+    /// The reads are volatile, to the compiler this static never changes.
+    fn high_water(&self) -> usize {
+        let bottom = self.data.as_ptr();
+        let untouched = (0..KERNEL_STACK_SIZE)
+            .take_while(|&i| unsafe { bottom.add(i).read_volatile() } == STACK_SENTINEL)
+            .count();
+        KERNEL_STACK_SIZE - untouched
+    }
     pub fn push_context(&self, trap_cx: TrapContext) -> usize {
         let trap_cx_ptr = (self.get_sp() - core::mem::size_of::<TrapContext>()) as *mut TrapContext;
         unsafe {
@@ -56,6 +79,18 @@ fn get_base_i(app_id: usize) -> usize {
     APP_BASE_ADDRESS + app_id * APP_SIZE_LIMIT
 }
 
+/// Check that `[ptr, ptr + len)` lies in app `app_id`'s image or user stack.
+pub fn is_user_range_valid(app_id: usize, ptr: usize, len: usize) -> bool {
+    let end = match ptr.checked_add(len) {
+        Some(end) => end,
+        None => return false,
+    };
+    let base = get_base_i(app_id);
+    let stack_top = USER_STACK[app_id].get_sp();
+    (ptr >= base && end <= base + APP_SIZE_LIMIT)
+        || (ptr >= stack_top - USER_STACK_SIZE && end <= stack_top)
+}
+
 /// Get the total number of applications.
 pub fn get_num_app() -> usize {
     extern "C" {
@@ -64,6 +99,11 @@ pub fn get_num_app() -> usize {
     unsafe { (_num_app as usize as *const usize).read_volatile() }
 }
 
+/// Get the descriptors configuring each app, see [`APP_DESCRIPTORS`].
+pub fn get_app_descriptors() -> &'static [AppDescriptor] {
+    APP_DESCRIPTORS
+}
+
 /// Load nth user app at
 /// [APP_BASE_ADDRESS + n * APP_SIZE_LIMIT, APP_BASE_ADDRESS + (n+1) * APP_SIZE_LIMIT).
 pub fn load_apps() {
@@ -79,19 +119,56 @@ pub fn load_apps() {
     }
     // load apps
     for i in 0..num_app {
-        let base_i = get_base_i(i);
-        // clear region
-        (base_i..base_i + APP_SIZE_LIMIT)
-            .for_each(|addr| unsafe { (addr as *mut u8).write_volatile(0) });
-        // load app from data section to memory
-        let src = unsafe {
-            core::slice::from_raw_parts(app_start[i] as *const u8, app_start[i + 1] - app_start[i])
-        };
-        let dst = unsafe { core::slice::from_raw_parts_mut(base_i as *mut u8, src.len()) };
-        dst.copy_from_slice(src);
+        load_app(app_start, i);
+    }
+    // mark the kernel stacks for `stack_high_water`
+    for stack in KERNEL_STACK.iter().take(num_app) {
+        stack.fill_sentinel();
     }
 }
 
+/// Copy app `app_id` from the kernel data section into its slot.
+fn load_app(app_start: &[usize], app_id: usize) {
+    let base_i = get_base_i(app_id);
+    // clear region
+    (base_i..base_i + APP_SIZE_LIMIT)
+        .for_each(|addr| unsafe { (addr as *mut u8).write_volatile(0) });
+    // load app from data section to memory
+    let src = unsafe {
+        core::slice::from_raw_parts(
+            app_start[app_id] as *const u8,
+            app_start[app_id + 1] - app_start[app_id],
+        )
+    };
+    let dst = unsafe { core::slice::from_raw_parts_mut(base_i as *mut u8, src.len()) };
+    dst.copy_from_slice(src);
+}
+
+/// Load app `app_id` again, undoing whatever its last run wrote to its image.
+pub fn reload_app(app_id: usize) {
+    extern "C" {
+        fn _num_app();
+    }
+    let num_app_ptr = _num_app as usize as *const usize;
+    let app_start = unsafe { core::slice::from_raw_parts(num_app_ptr.add(1), get_num_app() + 1) };
+    load_app(app_start, app_id);
+    // the old image may still sit in the i-cache
+    unsafe {
+        core::arch::asm!("fence.i");
+    }
+}
+
+/// Most bytes of its kernel stack app `app_id` has used since boot.
+///
+/// [`load_apps`] fills every kernel stack with [`STACK_SENTINEL`], so this
+/// is the distance from the top of the stack to the deepest byte written.
+/// Stacks are not refilled on restart, the mark covers every run of a slot.
+/// A frame that happens to store the sentinel byte at its lowest address
+/// makes this underestimate by that byte.
+pub fn stack_high_water(app_id: usize) -> usize {
+    KERNEL_STACK[app_id].high_water()
+}
+
 /// get app info with entry and sp and save `TrapContext` in kernel stack
 pub fn init_app_cx(app_id: usize) -> usize {
     KERNEL_STACK[app_id].push_context(TrapContext::app_init_context(
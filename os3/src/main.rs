@@ -63,6 +63,7 @@ extern "C" {
     fn edata();
     fn sbss();
     fn ebss();
+    #[allow(unused)]
     fn ekernel();
     fn boot_stack();
     fn boot_stack_top();
@@ -80,8 +81,10 @@ pub fn rust_main() -> ! {
     println!("stack: [{:#x}, {:#x})", boot_stack as usize, boot_stack_top as usize);
     println!("[kernel] Hello, world!");
     heap_alloc::init_heap();
+    task::dispatch_jitter_test();
     trap::init();
     loader::load_apps();
+    task::ensure_initialized();
     trap::enable_timer_interrupt();
     timer::set_next_trigger();
     task::run_first_task();
@@ -6,6 +6,12 @@ const SBI_SET_TIMER: usize = 0;
 const SBI_CONSOLE_PUTCHAR: usize = 1;
 const SBI_CONSOLE_GETCHAR: usize = 2;
 const SBI_SHUTDOWN: usize = 8;
+const SBI_EXT_SRST: usize = 0x5352_5354;
+
+const SRST_TYPE_SHUTDOWN: usize = 0;
+const SRST_TYPE_COLD_REBOOT: usize = 1;
+const SRST_REASON_NONE: usize = 0;
+const SRST_REASON_FAILURE: usize = 1;
 
 #[inline(always)]
 /// general sbi call
@@ -44,3 +50,20 @@ pub fn shutdown() -> ! {
     sbi_call(SBI_SHUTDOWN, 0, 0, 0);
     panic!("It should shutdown!");
 }
+
+/// use the sbi SRST extension to shutdown, reporting whether it is due to a failure
+pub fn system_shutdown(failure: bool) -> ! {
+    let reason = if failure {
+        SRST_REASON_FAILURE
+    } else {
+        SRST_REASON_NONE
+    };
+    sbi_call(SBI_EXT_SRST, SRST_TYPE_SHUTDOWN, reason, 0);
+    panic!("It should shutdown!");
+}
+
+/// use the sbi SRST extension to cold reboot the machine
+pub fn reboot() -> ! {
+    sbi_call(SBI_EXT_SRST, SRST_TYPE_COLD_REBOOT, SRST_REASON_NONE, 0);
+    panic!("It should reboot!");
+}
@@ -12,26 +12,326 @@
 
 const SYSCALL_WRITE: usize = 64;
 const SYSCALL_EXIT: usize = 93;
+const SYSCALL_SLEEP: usize = 101;
 const SYSCALL_YIELD: usize = 124;
+const SYSCALL_SET_PRIORITY: usize = 140;
 const SYSCALL_GET_TIME: usize = 169;
+const SYSCALL_GET_TIME_ANOMALIES: usize = 403;
+const SYSCALL_SLEEP_UNTIL: usize = 404;
+const SYSCALL_PREDICT_SCHEDULE: usize = 405;
+const SYSCALL_SET_AUTO_FREEZE_THRESHOLD: usize = 406;
+const SYSCALL_TASK_STATS: usize = 407;
 const SYSCALL_TASK_INFO: usize = 410;
+const SYSCALL_SWAP_PRIORITIES: usize = 411;
+const SYSCALL_GET_QUANTUM_TIME: usize = 412;
+const SYSCALL_SET_PANIC_POLICY: usize = 413;
+const SYSCALL_SNAPSHOT_SYSCALLS: usize = 414;
+const SYSCALL_SET_TASK_NAME: usize = 415;
+const SYSCALL_EXPORT_TASKS_CSV: usize = 416;
+const SYSCALL_GET_REMAINING_SLICE: usize = 417;
+const SYSCALL_GET_LAST_EXITED: usize = 418;
+const SYSCALL_BARRIER_INIT: usize = 419;
+const SYSCALL_BARRIER_WAIT: usize = 420;
+const SYSCALL_SET_TRACE_ENABLED: usize = 421;
+const SYSCALL_DUMP_TRACE: usize = 422;
+const SYSCALL_GET_RUN_TIME_STDDEV: usize = 423;
+const SYSCALL_SET_FALLBACK_TASK: usize = 424;
+const SYSCALL_GET_TASK_BRIEF: usize = 425;
+const SYSCALL_SET_SYSCALL_VALIDATION: usize = 426;
+const SYSCALL_SET_ACCOUNT_SELF_OVERHEAD: usize = 427;
+const SYSCALL_GET_RECENT_SWITCH_LATENCY: usize = 428;
+const SYSCALL_SWITCH_TO: usize = 429;
+const SYSCALL_GET_INIT_DURATION: usize = 430;
+const SYSCALL_PEEK_NEXT_TASK: usize = 431;
+const SYSCALL_RECORD_SCHEDULE: usize = 432;
+const SYSCALL_STOP_RECORDING: usize = 433;
+const SYSCALL_REPLAY_SCHEDULE: usize = 434;
+const SYSCALL_IS_SYSTEM_IDLE: usize = 435;
+const SYSCALL_SET_MAX_SWITCHES: usize = 436;
+const SYSCALL_GET_PRIORITY_INVERSIONS: usize = 437;
+const SYSCALL_SET_MIN_SHARE: usize = 438;
+const SYSCALL_DUMP_TASK_TABLE: usize = 439;
+const SYSCALL_GET_SYSTEM_SYSCALL_TIMES: usize = 440;
+const SYSCALL_UPTIME_TICKS: usize = 441;
+const SYSCALL_DUMP_SWITCH_TRACE: usize = 442;
+const SYSCALL_RESET_SYSCALL_STATS: usize = 443;
+const SYSCALL_GET_IDLE_TIME: usize = 444;
+const SYSCALL_TIME_SINCE: usize = 445;
+const SYSCALL_SET_CPU_BUDGET: usize = 446;
+const SYSCALL_GET_DISPATCH_COVERAGE: usize = 447;
+const SYSCALL_SET_TIME_SLICE: usize = 448;
+const SYSCALL_RESTART_TASK: usize = 449;
+const SYSCALL_GET_PREV_RUN_INFO: usize = 450;
+const SYSCALL_WAITTASK: usize = 451;
+const SYSCALL_LIST_TASKS_BY_STATUS: usize = 452;
+const SYSCALL_SLEEP_US: usize = 453;
+const SYSCALL_PRIORITY_BOOST_BEGIN: usize = 454;
+const SYSCALL_PRIORITY_BOOST_END: usize = 455;
+const SYSCALL_GET_LRU_TASK: usize = 456;
+const SYSCALL_GET_SCHED_OVERHEAD: usize = 457;
+const SYSCALL_STATE_FINGERPRINT: usize = 458;
+const SYSCALL_SET_DEADLINE: usize = 459;
+const SYSCALL_TOTAL_SYSTEM_SYSCALLS: usize = 474;
+const SYSCALL_SOFT_RESET: usize = 475;
+const SYSCALL_GET_SLOT_IDLE: usize = 476;
+const SYSCALL_GET_DISPATCH_BALANCE: usize = 477;
+const SYSCALL_NEXT_WAKE_TIME: usize = 478;
+const SYSCALL_GET_KERNEL_ENTRIES: usize = 479;
+const SYSCALL_GET_TASK_RECORD: usize = 480;
+const SYSCALL_SET_SYSCALL_STORM_THRESHOLD: usize = 481;
+const SYSCALL_LIST_UNINIT_SLOTS: usize = 482;
+const SYSCALL_STACK_HIGH_WATER: usize = 483;
+const SYSCALL_SET_COUNT_FAILURES: usize = 484;
+const SYSCALL_LIST_RELATED: usize = 485;
+const SYSCALL_SET_KERNEL_RESIDENCY_WARN: usize = 486;
+const SYSCALL_GET_BUSIEST_TASK: usize = 487;
+const SYSCALL_TASK_INFO_BY_ID: usize = 488;
+const SYSCALL_SET_PREEMPTION: usize = 489;
+const SYSCALL_EXTEND_BUDGET: usize = 490;
+const SYSCALL_GET_TIME_BREAKDOWN: usize = 491;
+const SYSCALL_SET_COUNTING: usize = 492;
+const SYSCALL_GET_CPU_GINI: usize = 493;
+const SYSCALL_GET_SELECTION_STATS: usize = 494;
+const SYSCALL_GET_TASK_STATUSES: usize = 495;
+const SYSCALL_GET_MAX_IDLE_STRETCH: usize = 496;
+const SYSCALL_INJECT_FAULT: usize = 497;
+const SYSCALL_SET_GLOBAL_QUANTUM: usize = 498;
+const SYSCALL_GET_GLOBAL_QUANTUM: usize = 499;
 
 mod fs;
 mod process;
+mod sched;
+mod sync;
 
 use fs::*;
 use process::*;
-use crate::task::TaskInfo;
+use sched::*;
+use sync::*;
+use crate::config::MAX_SYSCALL_NUM;
+use crate::loader::is_user_range_valid;
+use crate::task::{
+    add_system_syscall_time, current_task_id, SwitchLatency, SwitchTraceDump, TaskDumpEntry,
+    TaskInfo, TaskInfoSummary, TaskStats, TimeBreakdown, TraceDump,
+};
+use crate::timer::{get_time_us, time_delta};
+
+/// whether `syscall_id` is handled by [`syscall()`]
+pub fn is_known_syscall(syscall_id: usize) -> bool {
+    matches!(
+        syscall_id,
+        SYSCALL_WRITE
+            | SYSCALL_EXIT
+            | SYSCALL_SLEEP
+            | SYSCALL_YIELD
+            | SYSCALL_SET_PRIORITY
+            | SYSCALL_GET_TIME
+            | SYSCALL_GET_TIME_ANOMALIES
+            | SYSCALL_SLEEP_UNTIL
+            | SYSCALL_PREDICT_SCHEDULE
+            | SYSCALL_SET_AUTO_FREEZE_THRESHOLD
+            | SYSCALL_TASK_STATS
+            | SYSCALL_TASK_INFO
+            | SYSCALL_SWAP_PRIORITIES
+            | SYSCALL_GET_QUANTUM_TIME
+            | SYSCALL_SET_PANIC_POLICY
+            | SYSCALL_SNAPSHOT_SYSCALLS
+            | SYSCALL_SET_TASK_NAME
+            | SYSCALL_EXPORT_TASKS_CSV
+            | SYSCALL_GET_REMAINING_SLICE
+            | SYSCALL_GET_LAST_EXITED
+            | SYSCALL_BARRIER_INIT
+            | SYSCALL_BARRIER_WAIT
+            | SYSCALL_SET_TRACE_ENABLED
+            | SYSCALL_DUMP_TRACE
+            | SYSCALL_GET_RUN_TIME_STDDEV
+            | SYSCALL_SET_FALLBACK_TASK
+            | SYSCALL_GET_TASK_BRIEF
+            | SYSCALL_SET_SYSCALL_VALIDATION
+            | SYSCALL_SET_ACCOUNT_SELF_OVERHEAD
+            | SYSCALL_GET_RECENT_SWITCH_LATENCY
+            | SYSCALL_SWITCH_TO
+            | SYSCALL_GET_INIT_DURATION
+            | SYSCALL_PEEK_NEXT_TASK
+            | SYSCALL_RECORD_SCHEDULE
+            | SYSCALL_STOP_RECORDING
+            | SYSCALL_REPLAY_SCHEDULE
+            | SYSCALL_IS_SYSTEM_IDLE
+            | SYSCALL_SET_MAX_SWITCHES
+            | SYSCALL_GET_PRIORITY_INVERSIONS
+            | SYSCALL_SET_MIN_SHARE
+            | SYSCALL_DUMP_TASK_TABLE
+            | SYSCALL_GET_SYSTEM_SYSCALL_TIMES
+            | SYSCALL_UPTIME_TICKS
+            | SYSCALL_DUMP_SWITCH_TRACE
+            | SYSCALL_RESET_SYSCALL_STATS
+            | SYSCALL_GET_IDLE_TIME
+            | SYSCALL_TIME_SINCE
+            | SYSCALL_SET_CPU_BUDGET
+            | SYSCALL_GET_DISPATCH_COVERAGE
+            | SYSCALL_SET_TIME_SLICE
+            | SYSCALL_RESTART_TASK
+            | SYSCALL_GET_PREV_RUN_INFO
+            | SYSCALL_WAITTASK
+            | SYSCALL_LIST_TASKS_BY_STATUS
+            | SYSCALL_SLEEP_US
+            | SYSCALL_PRIORITY_BOOST_BEGIN
+            | SYSCALL_PRIORITY_BOOST_END
+            | SYSCALL_GET_LRU_TASK
+            | SYSCALL_GET_SCHED_OVERHEAD
+            | SYSCALL_STATE_FINGERPRINT
+            | SYSCALL_SET_DEADLINE
+            | SYSCALL_TOTAL_SYSTEM_SYSCALLS
+            | SYSCALL_SOFT_RESET
+            | SYSCALL_GET_SLOT_IDLE
+            | SYSCALL_GET_DISPATCH_BALANCE
+            | SYSCALL_NEXT_WAKE_TIME
+            | SYSCALL_GET_KERNEL_ENTRIES
+            | SYSCALL_GET_TASK_RECORD
+            | SYSCALL_SET_SYSCALL_STORM_THRESHOLD
+            | SYSCALL_LIST_UNINIT_SLOTS
+            | SYSCALL_STACK_HIGH_WATER
+            | SYSCALL_SET_COUNT_FAILURES
+            | SYSCALL_LIST_RELATED
+            | SYSCALL_SET_KERNEL_RESIDENCY_WARN
+            | SYSCALL_GET_BUSIEST_TASK
+            | SYSCALL_TASK_INFO_BY_ID
+            | SYSCALL_SET_PREEMPTION
+            | SYSCALL_EXTEND_BUDGET
+            | SYSCALL_GET_TIME_BREAKDOWN
+            | SYSCALL_SET_COUNTING
+            | SYSCALL_GET_CPU_GINI
+            | SYSCALL_GET_SELECTION_STATS
+            | SYSCALL_GET_TASK_STATUSES
+            | SYSCALL_GET_MAX_IDLE_STRETCH
+            | SYSCALL_INJECT_FAULT
+            | SYSCALL_SET_GLOBAL_QUANTUM
+            | SYSCALL_GET_GLOBAL_QUANTUM
+    )
+}
+
+/// whether `syscall_id` does I/O, counted towards a task looking interactive
+pub fn is_io_syscall(syscall_id: usize) -> bool {
+    matches!(syscall_id, SYSCALL_WRITE)
+}
+
+/// copy up to `len` of `ids` into the user buffer `buf`; returns how many, -1 if `buf` is invalid
+fn copy_ids_to_user(buf: *mut usize, len: usize, ids: &[usize]) -> isize {
+    let size = len.saturating_mul(core::mem::size_of::<usize>());
+    if !is_user_range_valid(current_task_id(), buf as usize, size) {
+        return -1;
+    }
+    let n = ids.len().min(len);
+    let dst = unsafe { core::slice::from_raw_parts_mut(buf, n) };
+    dst.copy_from_slice(&ids[..n]);
+    n as isize
+}
 
 /// handle syscall exception with `syscall_id` and other arguments
+///
+/// Every id handled here must also be listed in [`is_known_syscall`]. The
+/// time until the call returns to its caller is added to the system-wide
+/// per-syscall times, so a yield includes whatever ran in between.
 pub fn syscall(syscall_id: usize, args: [usize; 3]) -> isize {
     // LAB1: You may need to update syscall info here.
-    match syscall_id {
+    let start = get_time_us();
+    let ret = match syscall_id {
         SYSCALL_WRITE => sys_write(args[0], args[1] as *const u8, args[2]),
         SYSCALL_EXIT => sys_exit(args[0] as i32),
+        SYSCALL_SLEEP => sys_sleep(args[0]),
         SYSCALL_YIELD => sys_yield(),
+        SYSCALL_SET_PRIORITY => sys_set_priority(args[0] as isize),
         SYSCALL_GET_TIME => sys_get_time(args[0] as *mut TimeVal, args[1]),
+        SYSCALL_GET_TIME_ANOMALIES => sys_get_time_anomalies(),
+        SYSCALL_SLEEP_UNTIL => sys_sleep_until(args[0]),
+        SYSCALL_PREDICT_SCHEDULE => sys_predict_schedule(args[0] as *mut usize, args[1]),
+        SYSCALL_SET_AUTO_FREEZE_THRESHOLD => sys_set_auto_freeze_threshold(args[0]),
+        SYSCALL_TASK_STATS => sys_task_stats(args[0], args[1] as *mut TaskStats),
         SYSCALL_TASK_INFO => sys_task_info(args[0] as *mut TaskInfo),
+        SYSCALL_SWAP_PRIORITIES => sys_swap_priorities(args[0], args[1]),
+        SYSCALL_GET_QUANTUM_TIME => sys_get_quantum_time(),
+        SYSCALL_SET_PANIC_POLICY => sys_set_panic_policy(args[0]),
+        SYSCALL_SNAPSHOT_SYSCALLS => sys_snapshot_syscalls(args[0] as *mut [u32; MAX_SYSCALL_NUM]),
+        SYSCALL_SET_TASK_NAME => sys_set_task_name(args[0] as *const u8, args[1]),
+        SYSCALL_EXPORT_TASKS_CSV => sys_export_tasks_csv(args[0] as *mut u8, args[1]),
+        SYSCALL_GET_REMAINING_SLICE => sys_get_remaining_slice(),
+        SYSCALL_GET_LAST_EXITED => sys_get_last_exited(args[0] as *mut usize),
+        SYSCALL_BARRIER_INIT => sys_barrier_init(args[0], args[1]),
+        SYSCALL_BARRIER_WAIT => sys_barrier_wait(args[0]),
+        SYSCALL_SET_TRACE_ENABLED => sys_set_trace_enabled(args[0], args[1] != 0),
+        SYSCALL_DUMP_TRACE => sys_dump_trace(args[0], args[1] as *mut TraceDump),
+        SYSCALL_GET_RUN_TIME_STDDEV => sys_get_run_time_stddev(),
+        SYSCALL_SET_FALLBACK_TASK => sys_set_fallback_task(args[0]),
+        SYSCALL_GET_TASK_BRIEF => sys_get_task_brief(),
+        SYSCALL_SET_SYSCALL_VALIDATION => sys_set_syscall_validation(args[0] != 0),
+        SYSCALL_SET_ACCOUNT_SELF_OVERHEAD => sys_set_account_self_overhead(args[0] != 0),
+        SYSCALL_GET_RECENT_SWITCH_LATENCY => {
+            sys_get_recent_switch_latency(args[0] as *mut SwitchLatency)
+        }
+        SYSCALL_SWITCH_TO => sys_switch_to(args[0]),
+        SYSCALL_GET_INIT_DURATION => sys_get_init_duration(),
+        SYSCALL_PEEK_NEXT_TASK => sys_peek_next_task(args[0] as *mut usize),
+        SYSCALL_RECORD_SCHEDULE => sys_record_schedule(),
+        SYSCALL_STOP_RECORDING => sys_stop_recording(args[0] as *mut usize, args[1]),
+        SYSCALL_REPLAY_SCHEDULE => sys_replay_schedule(args[0] as *const usize, args[1]),
+        SYSCALL_IS_SYSTEM_IDLE => sys_is_system_idle(),
+        SYSCALL_SET_MAX_SWITCHES => sys_set_max_switches(args[0]),
+        SYSCALL_GET_PRIORITY_INVERSIONS => sys_get_priority_inversions(),
+        SYSCALL_SET_MIN_SHARE => sys_set_min_share(args[0], args[1]),
+        SYSCALL_DUMP_TASK_TABLE => sys_dump_task_table(args[0] as *mut TaskDumpEntry, args[1]),
+        SYSCALL_GET_SYSTEM_SYSCALL_TIMES => {
+            sys_get_system_syscall_times(args[0] as *mut [u64; MAX_SYSCALL_NUM])
+        }
+        SYSCALL_UPTIME_TICKS => sys_uptime_ticks(),
+        SYSCALL_DUMP_SWITCH_TRACE => sys_dump_switch_trace(args[0] as *mut SwitchTraceDump),
+        SYSCALL_RESET_SYSCALL_STATS => sys_reset_syscall_stats(),
+        SYSCALL_GET_IDLE_TIME => sys_get_idle_time(),
+        SYSCALL_TIME_SINCE => sys_time_since(args[0]),
+        SYSCALL_SET_CPU_BUDGET => sys_set_cpu_budget(args[0], args[1]),
+        SYSCALL_GET_DISPATCH_COVERAGE => sys_get_dispatch_coverage(args[0] as *mut usize),
+        SYSCALL_SET_TIME_SLICE => sys_set_time_slice(args[0], args[1]),
+        SYSCALL_RESTART_TASK => sys_restart_task(args[0]),
+        SYSCALL_GET_PREV_RUN_INFO => {
+            sys_get_prev_run_info(args[0], args[1] as *mut TaskInfoSummary)
+        }
+        SYSCALL_WAITTASK => sys_waittask(args[0], args[1]),
+        SYSCALL_LIST_TASKS_BY_STATUS => {
+            sys_list_tasks_by_status(args[0], args[1] as *mut usize, args[2])
+        }
+        SYSCALL_SLEEP_US => sys_sleep_us(args[0]),
+        SYSCALL_PRIORITY_BOOST_BEGIN => sys_priority_boost_begin(),
+        SYSCALL_PRIORITY_BOOST_END => sys_priority_boost_end(),
+        SYSCALL_GET_LRU_TASK => sys_get_lru_task(),
+        SYSCALL_GET_SCHED_OVERHEAD => sys_get_sched_overhead(),
+        SYSCALL_STATE_FINGERPRINT => sys_state_fingerprint(),
+        SYSCALL_SET_DEADLINE => sys_set_deadline(args[0]),
+        SYSCALL_TOTAL_SYSTEM_SYSCALLS => sys_total_system_syscalls(),
+        SYSCALL_SOFT_RESET => sys_soft_reset(),
+        SYSCALL_GET_SLOT_IDLE => sys_get_slot_idle(args[0]),
+        SYSCALL_GET_DISPATCH_BALANCE => sys_get_dispatch_balance(),
+        SYSCALL_NEXT_WAKE_TIME => sys_next_wake_time(),
+        SYSCALL_GET_KERNEL_ENTRIES => sys_get_kernel_entries(),
+        SYSCALL_GET_TASK_RECORD => sys_get_task_record(args[0], args[1] as *mut u8, args[2]),
+        SYSCALL_SET_SYSCALL_STORM_THRESHOLD => sys_set_syscall_storm_threshold(args[0]),
+        SYSCALL_LIST_UNINIT_SLOTS => sys_list_uninit_slots(args[0] as *mut usize, args[1]),
+        SYSCALL_STACK_HIGH_WATER => sys_stack_high_water(args[0]),
+        SYSCALL_SET_COUNT_FAILURES => sys_set_count_failures(args[0] != 0),
+        SYSCALL_LIST_RELATED => sys_list_related(args[0], args[1] as *mut usize, args[2]),
+        SYSCALL_SET_KERNEL_RESIDENCY_WARN => sys_set_kernel_residency_warn(args[0]),
+        SYSCALL_GET_BUSIEST_TASK => sys_get_busiest_task(),
+        SYSCALL_TASK_INFO_BY_ID => sys_task_info_by_id(args[0], args[1] as *mut TaskInfo),
+        SYSCALL_SET_PREEMPTION => sys_set_preemption(args[0] != 0),
+        SYSCALL_EXTEND_BUDGET => sys_extend_budget(args[0], args[1]),
+        SYSCALL_GET_TIME_BREAKDOWN => sys_get_time_breakdown(args[0] as *mut TimeBreakdown),
+        SYSCALL_SET_COUNTING => sys_set_counting(args[0] != 0),
+        SYSCALL_GET_CPU_GINI => sys_get_cpu_gini(),
+        SYSCALL_GET_SELECTION_STATS => sys_get_selection_stats(args[0] as *mut usize),
+        SYSCALL_GET_TASK_STATUSES => sys_get_task_statuses(args[0] as *mut u8, args[1]),
+        SYSCALL_GET_MAX_IDLE_STRETCH => sys_get_max_idle_stretch(),
+        SYSCALL_INJECT_FAULT => sys_inject_fault(args[0]),
+        SYSCALL_SET_GLOBAL_QUANTUM => sys_set_global_quantum(args[0]),
+        SYSCALL_GET_GLOBAL_QUANTUM => sys_get_global_quantum(),
         _ => panic!("Unsupported syscall_id: {}", syscall_id),
-    }
+    };
+    add_system_syscall_time(syscall_id, time_delta(get_time_us(), start));
+    ret
 }
@@ -1,7 +1,20 @@
 //! Process management syscalls
 
-use crate::task::{exit_current_and_run_next, suspend_current_and_run_next, get_current_task_info, TaskInfo};
-use crate::timer::get_time_us;
+use super::copy_ids_to_user;
+use crate::config::MAX_SYSCALL_NUM;
+use crate::lang_items::{set_panic_policy, PanicPolicy};
+use crate::loader::{get_num_app, is_user_range_valid, stack_high_water};
+use crate::task::{
+    crash_current_and_run_next, current_task_id, dump_trace, exit_current_and_run_next,
+    get_current_kernel_entries, get_current_task_info, get_init_duration, get_prev_run_info,
+    get_slot_idle_us, get_system_syscall_times, get_task_info_by_id, get_task_stats,
+    get_time_breakdown, list_related, reset_syscall_stats, restart_task, set_account_self_overhead,
+    set_count_failures, set_current_counting, set_current_task_name, set_kernel_residency_warn,
+    set_syscall_storm_threshold, set_syscall_validation, set_trace_enabled, snapshot_syscalls,
+    soft_reset, total_system_syscalls, yield_current_and_run_next, TaskInfo, TaskInfoSummary,
+    TaskStats, TimeBreakdown, TraceDump, TrapCause,
+};
+use crate::timer::{get_time_ticks, get_time_us, time_anomaly_count, time_since};
 
 #[repr(C)]
 #[derive(Debug)]
@@ -19,7 +32,7 @@ pub fn sys_exit(exit_code: i32) -> ! {
 
 /// current task gives up resources for other tasks
 pub fn sys_yield() -> isize {
-    suspend_current_and_run_next();
+    yield_current_and_run_next();
     0
 }
 
@@ -42,3 +55,275 @@ pub fn sys_task_info(ti: *mut TaskInfo) -> isize {
     }
     0
 }
+
+/// fill `ti` with the info of task `id`, -1 if `id` is not loaded
+pub fn sys_task_info_by_id(id: usize, ti: *mut TaskInfo) -> isize {
+    let size = core::mem::size_of::<TaskInfo>();
+    if !is_user_range_valid(current_task_id(), ti as usize, size) {
+        return -1;
+    }
+    match get_task_info_by_id(id) {
+        Some(info) => {
+            unsafe {
+                *ti = info;
+            }
+            0
+        }
+        None => -1,
+    }
+}
+
+/// fill `ts` with the extended stats of task `id`, -1 if `id` is not loaded
+pub fn sys_task_stats(id: usize, ts: *mut TaskStats) -> isize {
+    let size = core::mem::size_of::<TaskStats>();
+    if !is_user_range_valid(current_task_id(), ts as usize, size) {
+        return -1;
+    }
+    match get_task_stats(id) {
+        Some(stats) => {
+            unsafe {
+                *ts = stats;
+            }
+            0
+        }
+        None => -1,
+    }
+}
+
+/// fill `tb` with where the current task's time went, all parts read at once
+pub fn sys_get_time_breakdown(tb: *mut TimeBreakdown) -> isize {
+    let size = core::mem::size_of::<TimeBreakdown>();
+    if !is_user_range_valid(current_task_id(), tb as usize, size) {
+        return -1;
+    }
+    unsafe {
+        *tb = get_time_breakdown();
+    }
+    0
+}
+
+/// copy the current task's per-syscall counts to `buf`
+pub fn sys_snapshot_syscalls(buf: *mut [u32; MAX_SYSCALL_NUM]) -> isize {
+    let size = core::mem::size_of::<[u32; MAX_SYSCALL_NUM]>();
+    if !is_user_range_valid(current_task_id(), buf as usize, size) {
+        return -1;
+    }
+    unsafe {
+        *buf = snapshot_syscalls();
+    }
+    0
+}
+
+/// set a display name for the current task, names longer than `TASK_NAME_LEN` are truncated
+pub fn sys_set_task_name(name: *const u8, len: usize) -> isize {
+    if !is_user_range_valid(current_task_id(), name as usize, len) {
+        return -1;
+    }
+    let name = unsafe { core::slice::from_raw_parts(name, len) };
+    set_current_task_name(name);
+    0
+}
+
+/// choose how the kernel stops: 0 panic, 1 halt, 2 reboot, 3 shutdown
+pub fn sys_set_panic_policy(policy: usize) -> isize {
+    let policy = match policy {
+        0 => PanicPolicy::Panic,
+        1 => PanicPolicy::Halt,
+        2 => PanicPolicy::Reboot,
+        3 => PanicPolicy::Shutdown,
+        _ => return -1,
+    };
+    set_panic_policy(policy);
+    0
+}
+
+/// turn syscall tracing of task `id` on or off
+pub fn sys_set_trace_enabled(id: usize, enabled: bool) -> isize {
+    if set_trace_enabled(id, enabled) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// copy the syscall trace of task `id` to `dump`
+pub fn sys_dump_trace(id: usize, dump: *mut TraceDump) -> isize {
+    let size = core::mem::size_of::<TraceDump>();
+    if !is_user_range_valid(current_task_id(), dump as usize, size) {
+        return -1;
+    }
+    match dump_trace(id) {
+        Some(trace) => {
+            unsafe {
+                *dump = trace;
+            }
+            0
+        }
+        None => -1,
+    }
+}
+
+/// turn checking syscall ids against the known table on or off for all tasks
+pub fn sys_set_syscall_validation(enabled: bool) -> isize {
+    set_syscall_validation(enabled);
+    0
+}
+
+/// turn charging the cost of `sys_task_info` to the caller's `info_overhead_us` on or off
+pub fn sys_set_account_self_overhead(enabled: bool) -> isize {
+    set_account_self_overhead(enabled);
+    0
+}
+
+/// get the time in microseconds the kernel took to set up the task manager
+pub fn sys_get_init_duration() -> isize {
+    get_init_duration() as isize
+}
+
+/// copy the total microseconds spent in each syscall by all tasks to `buf`
+pub fn sys_get_system_syscall_times(buf: *mut [u64; MAX_SYSCALL_NUM]) -> isize {
+    let size = core::mem::size_of::<[u64; MAX_SYSCALL_NUM]>();
+    if !is_user_range_valid(current_task_id(), buf as usize, size) {
+        return -1;
+    }
+    unsafe {
+        *buf = get_system_syscall_times();
+    }
+    0
+}
+
+/// get the raw hardware timer count, ticking at `CLOCK_FREQ` Hz
+pub fn sys_uptime_ticks() -> isize {
+    get_time_ticks() as isize
+}
+
+/// clear the current task's syscall counts
+pub fn sys_reset_syscall_stats() -> isize {
+    reset_syscall_stats();
+    0
+}
+
+/// get the microseconds elapsed since `epoch_us`, an earlier `get_time_us` value
+pub fn sys_time_since(epoch_us: usize) -> isize {
+    time_since(epoch_us) as isize
+}
+
+/// run exited task `id` again from the start, keeping the stats of the finished run
+pub fn sys_restart_task(id: usize) -> isize {
+    if restart_task(id) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// copy the stats of task `id`'s run before its last restart to `info`, -1 if never restarted
+pub fn sys_get_prev_run_info(id: usize, info: *mut TaskInfoSummary) -> isize {
+    let size = core::mem::size_of::<TaskInfoSummary>();
+    if !is_user_range_valid(current_task_id(), info as usize, size) {
+        return -1;
+    }
+    match get_prev_run_info(id) {
+        Some(summary) => {
+            unsafe {
+                *info = summary;
+            }
+            0
+        }
+        None => -1,
+    }
+}
+
+/// get the number of syscalls issued by all tasks since boot
+pub fn sys_total_system_syscalls() -> isize {
+    total_system_syscalls() as isize
+}
+
+/// reset every task to its boot state and start over from task 0, never returns
+pub fn sys_soft_reset() -> ! {
+    info!(
+        "[kernel] Soft reset requested by task {}",
+        current_task_id()
+    );
+    soft_reset()
+}
+
+/// get the microseconds slot `id` sat exited before its last restart, -1 if never restarted
+pub fn sys_get_slot_idle(id: usize) -> isize {
+    match get_slot_idle_us(id) {
+        Some(us) => us as isize,
+        None => -1,
+    }
+}
+
+/// get how many times the current task entered the kernel, this call included
+pub fn sys_get_kernel_entries() -> isize {
+    get_current_kernel_entries() as isize
+}
+
+/// warn when a task issues more than `per_sec` syscalls per second, 0 to never warn
+pub fn sys_set_syscall_storm_threshold(per_sec: usize) -> isize {
+    set_syscall_storm_threshold(per_sec);
+    0
+}
+
+/// get the most bytes of its kernel stack task `id` has used, -1 if `id` is not loaded
+pub fn sys_stack_high_water(id: usize) -> isize {
+    if id >= get_num_app() {
+        return -1;
+    }
+    stack_high_water(id) as isize
+}
+
+/// count every syscall when issued (the default), or with `false` only those that succeeded
+pub fn sys_set_count_failures(enabled: bool) -> isize {
+    set_count_failures(enabled);
+    0
+}
+
+/// write the ids of tasks related to task `id` by restarts into `buf`, at most `len`; returns how many
+pub fn sys_list_related(id: usize, buf: *mut usize, len: usize) -> isize {
+    match list_related(id) {
+        Some(ids) => copy_ids_to_user(buf, len, &ids),
+        None => -1,
+    }
+}
+
+/// warn when a trap keeps the kernel on the CPU for more than `us` microseconds, 0 to never warn
+pub fn sys_set_kernel_residency_warn(us: usize) -> isize {
+    set_kernel_residency_warn(us);
+    0
+}
+
+/// turn counting the current task's syscalls on (the default) or off, freezing its counters
+pub fn sys_set_counting(enabled: bool) -> isize {
+    set_current_counting(enabled);
+    0
+}
+
+/// kill the current task as if it hit the fault `kind`, a `TrapCause` discriminant
+///
+/// Only kernels built with the `fault-injection` feature support this, so
+/// a normal kernel cannot be made to fake crashes. Returns -1 if disabled or
+/// `kind` is not a fault, otherwise does not return.
+pub fn sys_inject_fault(kind: usize) -> isize {
+    if !cfg!(feature = "fault-injection") {
+        return -1;
+    }
+    match TrapCause::from_usize(kind) {
+        Some(TrapCause::PageFault) => {
+            error!("[kernel] Injected PageFault in application, core dumped.");
+        }
+        Some(TrapCause::IllegalInstruction) => {
+            error!("[kernel] Injected IllegalInstruction in application, core dumped.");
+        }
+        _ => return -1,
+    }
+    crash_current_and_run_next();
+    panic!("Unreachable in sys_inject_fault!");
+}
+
+/// get how many time deltas came out negative since boot, nonzero points to a timer problem
+pub fn sys_get_time_anomalies() -> isize {
+    time_anomaly_count() as isize
+}
@@ -0,0 +1,437 @@
+//! Scheduling-related syscalls
+
+use super::copy_ids_to_user;
+use crate::config::{MAX_APP_NUM, MAX_SCHEDULE_LEN, MIN_PRIORITY};
+use crate::loader::{get_num_app, is_user_range_valid};
+use crate::task::{
+    busiest_task_by_syscalls, cpu_gini_permille, current_task_id, dispatch_balance_permille,
+    dump_switch_trace, dump_task_table, encode_task_record, export_tasks_csv, extend_budget,
+    get_current_quantum_time_us, get_current_task_brief, get_dispatch_coverage,
+    get_global_quantum_ms, get_idle_time_us, get_last_exited, get_lru_task,
+    get_max_idle_stretch_us, get_priority_inversions, get_recent_switch_latency,
+    get_remaining_slice_us, get_sched_overhead_permille, get_selection_stats, is_system_idle,
+    list_tasks_by_status, list_uninit_slots_below, next_wake_time_us, peek_next_task,
+    predict_schedule, priority_boost_begin, priority_boost_end, record_schedule, replay_schedule,
+    run_time_stddev_ms, set_auto_freeze_threshold, set_cpu_budget, set_current_deadline,
+    set_current_priority, set_fallback_task, set_global_quantum_ms, set_max_switches,
+    set_min_share, set_preemption_enabled, set_time_slice, snapshot_statuses, state_fingerprint,
+    stop_recording, swap_priorities, switch_to, SwitchLatency, SwitchTraceDump, TaskDumpEntry,
+    TaskStatus,
+};
+
+/// swap the priorities of tasks `id_a` and `id_b`
+pub fn sys_swap_priorities(id_a: usize, id_b: usize) -> isize {
+    if swap_priorities(id_a, id_b) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// get the time in microseconds the current task has run since its last dispatch,
+/// or since a yield that picked it again
+///
+/// This is not `get_time_us() - last_dispatch_time`: a lone task picked again
+/// is not dispatched, so the time since its last dispatch keeps growing across
+/// its yields. The time is taken from the start of the current stretch
+/// instead, see `TaskControlBlock::resume`.
+pub fn sys_get_quantum_time() -> isize {
+    get_current_quantum_time_us() as isize
+}
+
+/// copy the task stats CSV into `buf`, truncated to `len` bytes; returns the bytes copied
+pub fn sys_export_tasks_csv(buf: *mut u8, len: usize) -> isize {
+    if !is_user_range_valid(current_task_id(), buf as usize, len) {
+        return -1;
+    }
+    let csv = export_tasks_csv();
+    let n = csv.len().min(len);
+    let dst = unsafe { core::slice::from_raw_parts_mut(buf, n) };
+    dst.copy_from_slice(&csv.as_bytes()[..n]);
+    n as isize
+}
+
+/// get the microseconds left in the current task's time slice, capped at `isize::MAX`
+pub fn sys_get_remaining_slice() -> isize {
+    get_remaining_slice_us().min(isize::MAX as usize) as isize
+}
+
+/// get the id of the task that exited last and store its exit time in `time_us`;
+/// returns -1 before any task has exited or if `time_us` is not writable
+pub fn sys_get_last_exited(time_us: *mut usize) -> isize {
+    let size = core::mem::size_of::<usize>();
+    if !is_user_range_valid(current_task_id(), time_us as usize, size) {
+        return -1;
+    }
+    let (id, exit_time) = get_last_exited();
+    if id == usize::MAX {
+        return -1;
+    }
+    unsafe {
+        *time_us = exit_time;
+    }
+    id as isize
+}
+
+/// get the standard deviation in milliseconds of the user time of all started tasks
+pub fn sys_get_run_time_stddev() -> isize {
+    run_time_stddev_ms() as isize
+}
+
+/// make task `id` the one to run when no other task is ready
+pub fn sys_set_fallback_task(id: usize) -> isize {
+    if set_fallback_task(id) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// get the current task's id and status packed as `id << 8 | status`
+pub fn sys_get_task_brief() -> isize {
+    let (id, status) = get_current_task_brief();
+    ((id << 8) | status as usize) as isize
+}
+
+/// summarize the latency of the last few context switches into `latency`, -1 before any switch
+pub fn sys_get_recent_switch_latency(latency: *mut SwitchLatency) -> isize {
+    let size = core::mem::size_of::<SwitchLatency>();
+    if !is_user_range_valid(current_task_id(), latency as usize, size) {
+        return -1;
+    }
+    match get_recent_switch_latency() {
+        Some(summary) => {
+            unsafe {
+                *latency = summary;
+            }
+            0
+        }
+        None => -1,
+    }
+}
+
+/// suspend the current task and run the `Ready` task `id` next, regardless of the policy
+pub fn sys_switch_to(id: usize) -> isize {
+    if switch_to(id) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// store the current task id in `current` and return the id that would run next, -1 if none
+pub fn sys_peek_next_task(current: *mut usize) -> isize {
+    let size = core::mem::size_of::<usize>();
+    if !is_user_range_valid(current_task_id(), current as usize, size) {
+        return -1;
+    }
+    let (id, next) = peek_next_task();
+    unsafe {
+        *current = id;
+    }
+    match next {
+        Some(next) => next as isize,
+        None => -1,
+    }
+}
+
+/// start recording the ids of dispatched tasks
+pub fn sys_record_schedule() -> isize {
+    record_schedule();
+    0
+}
+
+/// stop recording and copy up to `len` recorded ids into `buf`; returns the number
+/// recorded, at most `MAX_SCHEDULE_LEN`
+pub fn sys_stop_recording(buf: *mut usize, len: usize) -> isize {
+    // checked before stopping, a bad buffer must not end the recording
+    let size = len.saturating_mul(core::mem::size_of::<usize>());
+    if !is_user_range_valid(current_task_id(), buf as usize, size) {
+        return -1;
+    }
+    let schedule = stop_recording();
+    copy_ids_to_user(buf, len, &schedule);
+    schedule.len() as isize
+}
+
+/// make the next `len` dispatches follow the task ids in `buf`;
+/// -1 if `len` is above `MAX_SCHEDULE_LEN` or an id is not loaded
+pub fn sys_replay_schedule(buf: *const usize, len: usize) -> isize {
+    if len > MAX_SCHEDULE_LEN {
+        return -1;
+    }
+    let size = len.saturating_mul(core::mem::size_of::<usize>());
+    if !is_user_range_valid(current_task_id(), buf as usize, size) {
+        return -1;
+    }
+    let schedule = unsafe { core::slice::from_raw_parts(buf, len) };
+    if replay_schedule(schedule.to_vec()) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// return 1 if no task is ready or running, 0 otherwise
+pub fn sys_is_system_idle() -> isize {
+    is_system_idle() as isize
+}
+
+/// stop the kernel once more than `n` context switches happened, 0 for no limit
+pub fn sys_set_max_switches(n: usize) -> isize {
+    set_max_switches(n);
+    0
+}
+
+/// get how many dispatches passed over a runnable task of higher priority
+pub fn sys_get_priority_inversions() -> isize {
+    get_priority_inversions() as isize
+}
+
+/// guarantee task `id` at least `permille` of the CPU, -1 if that over-subscribes it
+pub fn sys_set_min_share(id: usize, permille: usize) -> isize {
+    if set_min_share(id, permille) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// fill `buf` with up to `len` rows of the task table; returns the rows written
+pub fn sys_dump_task_table(buf: *mut TaskDumpEntry, len: usize) -> isize {
+    let size = len.saturating_mul(core::mem::size_of::<TaskDumpEntry>());
+    if !is_user_range_valid(current_task_id(), buf as usize, size) {
+        return -1;
+    }
+    let dst = unsafe { core::slice::from_raw_parts_mut(buf, len) };
+    dump_task_table(dst) as isize
+}
+
+/// copy the latest context switches to `dump`
+pub fn sys_dump_switch_trace(dump: *mut SwitchTraceDump) -> isize {
+    let size = core::mem::size_of::<SwitchTraceDump>();
+    if !is_user_range_valid(current_task_id(), dump as usize, size) {
+        return -1;
+    }
+    unsafe {
+        *dump = dump_switch_trace();
+    }
+    0
+}
+
+/// get the time in microseconds the kernel idled while all tasks slept
+pub fn sys_get_idle_time() -> isize {
+    get_idle_time_us() as isize
+}
+
+/// get the longest single stretch in microseconds the kernel idled while all tasks slept
+pub fn sys_get_max_idle_stretch() -> isize {
+    get_max_idle_stretch_us() as isize
+}
+
+/// limit task `id` to `us` microseconds of user time, 0 for no limit
+pub fn sys_set_cpu_budget(id: usize, us: usize) -> isize {
+    if set_cpu_budget(id, us) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// store the number of loaded apps in `total` and return how many were ever dispatched
+pub fn sys_get_dispatch_coverage(total: *mut usize) -> isize {
+    let size = core::mem::size_of::<usize>();
+    if !is_user_range_valid(current_task_id(), total as usize, size) {
+        return -1;
+    }
+    let (dispatched, num_app) = get_dispatch_coverage();
+    unsafe {
+        *total = num_app;
+    }
+    dispatched as isize
+}
+
+/// set the time slice of task `id` to `ms` milliseconds, -1 if below `MIN_TIME_SLICE_MS`
+pub fn sys_set_time_slice(id: usize, ms: usize) -> isize {
+    if set_time_slice(id, ms) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// fill `buf` with up to `len` ids of the tasks whose `TaskStatus` discriminant is `status`
+///
+/// Returns the ids written, -1 if `status` is not a valid discriminant.
+pub fn sys_list_tasks_by_status(status: usize, buf: *mut usize, len: usize) -> isize {
+    let status = match TaskStatus::from_usize(status) {
+        Some(status) => status,
+        None => return -1,
+    };
+    copy_ids_to_user(buf, len, &list_tasks_by_status(status))
+}
+
+/// run at the priority ceiling until the matching `sys_priority_boost_end`, calls nest
+pub fn sys_priority_boost_begin() -> isize {
+    priority_boost_begin();
+    0
+}
+
+/// end the innermost boosted region, -1 if none is open
+pub fn sys_priority_boost_end() -> isize {
+    if priority_boost_end() {
+        0
+    } else {
+        -1
+    }
+}
+
+/// get the id of the ready or running task that ran least recently, -1 if no task has run
+pub fn sys_get_lru_task() -> isize {
+    match get_lru_task() {
+        Some(id) => id as isize,
+        None => -1,
+    }
+}
+
+/// get the time spent scheduling in permille of the time since boot
+pub fn sys_get_sched_overhead() -> isize {
+    get_sched_overhead_permille() as isize
+}
+
+/// get a hash of all task statuses and dispatch counts, the bits of a `u64`
+pub fn sys_state_fingerprint() -> isize {
+    state_fingerprint() as isize
+}
+
+/// ask to be scheduled before tasks with later deadlines, `ms` from now; 0 clears it
+pub fn sys_set_deadline(ms: usize) -> isize {
+    set_current_deadline(ms);
+    0
+}
+
+/// set the current task's priority; returns `prio`, or -1 if it is below `MIN_PRIORITY`
+///
+/// As in the lab ABI any larger `prio` is accepted and returned as is, the
+/// task itself gets it clamped to `MAX_PRIORITY`.
+pub fn sys_set_priority(prio: isize) -> isize {
+    if prio < MIN_PRIORITY as isize {
+        return -1;
+    }
+    set_current_priority(prio as usize);
+    prio
+}
+
+/// get the fewest dispatches of any task over the most, in permille
+pub fn sys_get_dispatch_balance() -> isize {
+    dispatch_balance_permille() as isize
+}
+
+/// get the time in microseconds the first sleeping task is due to wake, -1 if none sleeps
+pub fn sys_next_wake_time() -> isize {
+    match next_wake_time_us() {
+        Some(us) => us as isize,
+        None => -1,
+    }
+}
+
+/// write the binary stats record of task `id` into `buf`; returns the bytes written, -1 on error
+pub fn sys_get_task_record(id: usize, buf: *mut u8, len: usize) -> isize {
+    if !is_user_range_valid(current_task_id(), buf as usize, len) {
+        return -1;
+    }
+    let dst = unsafe { core::slice::from_raw_parts_mut(buf, len) };
+    match encode_task_record(id, dst) {
+        0 => -1,
+        n => n as isize,
+    }
+}
+
+/// write the ids of loaded app slots still `UnInit` into `buf`, at most `len`; returns how many
+pub fn sys_list_uninit_slots(buf: *mut usize, len: usize) -> isize {
+    copy_ids_to_user(buf, len, &list_uninit_slots_below(get_num_app()))
+}
+
+/// get the id of the task that made the most syscalls, -1 if no syscall was counted yet
+pub fn sys_get_busiest_task() -> isize {
+    match busiest_task_by_syscalls() {
+        Some(id) => id as isize,
+        None => -1,
+    }
+}
+
+/// turn preemption by used up time slices on (the default) or off
+pub fn sys_set_preemption(enabled: bool) -> isize {
+    set_preemption_enabled(enabled);
+    0
+}
+
+/// set the time slice in milliseconds of tasks without one set by `sys_set_time_slice`
+pub fn sys_set_global_quantum(ms: usize) -> isize {
+    if set_global_quantum_ms(ms) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// get the time slice in milliseconds of tasks without one set by `sys_set_time_slice`
+pub fn sys_get_global_quantum() -> isize {
+    get_global_quantum_ms() as isize
+}
+
+/// freeze a task that runs `ms` milliseconds without giving up the CPU, 0 turns it off
+pub fn sys_set_auto_freeze_threshold(ms: usize) -> isize {
+    set_auto_freeze_threshold(ms);
+    0
+}
+
+/// give task `id` another `ms` milliseconds of user time on top of its budget, -1 if it has none
+pub fn sys_extend_budget(id: usize, ms: usize) -> isize {
+    if extend_budget(id, ms) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// get the Gini coefficient of the tasks' user times in permille, 0 for a perfectly even split
+pub fn sys_get_cpu_gini() -> isize {
+    cpu_gini_permille() as isize
+}
+
+/// get the microseconds spent picking the next task in total, the number of picks goes to `calls`
+pub fn sys_get_selection_stats(calls: *mut usize) -> isize {
+    let size = core::mem::size_of::<usize>();
+    if !is_user_range_valid(current_task_id(), calls as usize, size) {
+        return -1;
+    }
+    let (n, time_us) = get_selection_stats();
+    unsafe {
+        *calls = n;
+    }
+    time_us as isize
+}
+
+/// write the status of every slot as one byte each into `buf`, at most `len`; returns how many
+pub fn sys_get_task_statuses(buf: *mut u8, len: usize) -> isize {
+    if !is_user_range_valid(current_task_id(), buf as usize, len) {
+        return -1;
+    }
+    let statuses = snapshot_statuses();
+    let n = len.min(MAX_APP_NUM);
+    let dst = unsafe { core::slice::from_raw_parts_mut(buf, n) };
+    for (byte, status) in dst.iter_mut().zip(statuses.iter()) {
+        *byte = *status as u8;
+    }
+    n as isize
+}
+
+/// write the ids of the next `len` tasks the scheduler would pick into `buf`, at most
+/// `MAX_SCHEDULE_LEN`; returns how many
+///
+/// The forecast takes every switch as a yield, so the caller shows up in it too, and
+/// assumes no task changes state otherwise. Fewer come back once nothing is runnable.
+pub fn sys_predict_schedule(buf: *mut usize, len: usize) -> isize {
+    copy_ids_to_user(buf, len, &predict_schedule(len))
+}
@@ -0,0 +1,62 @@
+//! Synchronization syscalls
+
+use crate::task::{
+    barrier_init, barrier_wait, sleep_current_and_run_next, sleep_until_and_run_next, wait_task,
+    ExitReason,
+};
+
+/// put the current task to sleep for at least `ms` milliseconds
+pub fn sys_sleep(ms: usize) -> isize {
+    sleep_current_and_run_next(ms.saturating_mul(1000));
+    0
+}
+
+/// put the current task to sleep for at least `us` microseconds
+///
+/// Wake-ups happen on timer ticks, so the sleep lasts up to one tick longer.
+pub fn sys_sleep_us(us: usize) -> isize {
+    sleep_current_and_run_next(us);
+    0
+}
+
+/// put the current task to sleep until the time in microseconds reaches `abs_us`
+///
+/// Returns 0 after sleeping, or 1 right away if `abs_us` has already passed.
+pub fn sys_sleep_until(abs_us: usize) -> isize {
+    if sleep_until_and_run_next(abs_us) {
+        0
+    } else {
+        1
+    }
+}
+
+/// configure barrier `id` to release once `count` tasks have arrived;
+/// -1 if `count` is 0, tasks wait on `id`, or no barrier is left for a new `id`
+pub fn sys_barrier_init(id: usize, count: usize) -> isize {
+    if barrier_init(id, count) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// block until barrier `id` releases, -1 if it was never configured or can never fill
+pub fn sys_barrier_wait(id: usize) -> isize {
+    if barrier_wait(id) {
+        0
+    } else {
+        -1
+    }
+}
+
+/// block until task `id` exits or `timeout_ms` passes, 0 to wait without a timeout
+///
+/// Returns the `ExitReason` discriminant of `id`, -2 on timeout, -1 if `id`
+/// is out of range or the caller itself.
+pub fn sys_waittask(id: usize, timeout_ms: usize) -> isize {
+    match wait_task(id, timeout_ms) {
+        Some(ExitReason::None) => -2,
+        Some(reason) => reason as isize,
+        None => -1,
+    }
+}
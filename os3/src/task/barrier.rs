@@ -0,0 +1,62 @@
+//! Barriers tasks can wait on until enough of them have arrived
+
+use alloc::vec::Vec;
+
+/// A reusable barrier releasing its waiters once `required` tasks arrived.
+pub struct Barrier {
+    /// id chosen by userspace
+    pub id: usize,
+    /// number of arrivals that release the barrier
+    pub required: usize,
+    /// arrivals in the current round
+    pub arrived: usize,
+    /// ids of the tasks blocked in the current round
+    pub waiters: Vec<usize>,
+    /// ids of the tasks that arrived at least once, in order of first arrival
+    pub members: Vec<usize>,
+}
+
+impl Barrier {
+    pub fn new(id: usize, required: usize) -> Self {
+        Self {
+            id,
+            required,
+            arrived: 0,
+            waiters: Vec::new(),
+            members: Vec::new(),
+        }
+    }
+
+    /// Count an arrival of task `id`, making it a member.
+    pub fn arrive(&mut self, id: usize) {
+        self.arrived += 1;
+        if !self.members.contains(&id) {
+            self.members.push(id);
+        }
+    }
+
+    /// Drop member `id` for good, it no longer counts towards `required`.
+    ///
+    /// Returns `false`, leaving the barrier alone, if `id` never arrived.
+    pub fn leave(&mut self, id: usize) -> bool {
+        match self.members.iter().position(|&m| m == id) {
+            Some(pos) => {
+                self.members.remove(pos);
+                self.required = self.required.saturating_sub(1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether enough tasks arrived to release this round.
+    pub fn is_full(&self) -> bool {
+        self.arrived >= self.required
+    }
+
+    /// End the current round, returning the tasks to wake.
+    pub fn release(&mut self) -> Vec<usize> {
+        self.arrived = 0;
+        core::mem::take(&mut self.waiters)
+    }
+}
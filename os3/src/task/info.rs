@@ -1,8 +1,215 @@
-use crate::config::MAX_SYSCALL_NUM;
-use super::TaskStatus;
+use crate::config::{MAX_SYSCALL_NUM, SWITCH_TRACE_LEN, TRACE_BUF_LEN, TRAP_CAUSE_NUM};
+use crate::timer::time_delta;
+use super::{ExitReason, SelectReason, SwitchReason, TaskClass, TaskControlBlock, TaskStatus};
 
+/// The lab's task info, declared field for field like the user lib's
+///
+/// Everything else the kernel tracks about a task is in [`TaskStats`].
 pub struct TaskInfo {
     pub status: TaskStatus,
     pub syscall_times: [u32; MAX_SYSCALL_NUM],
     pub time: usize,
-}
\ No newline at end of file
+}
+
+impl TaskInfo {
+    /// The info of `tcb` at `now`, `time` is 0 for a task that was never dispatched.
+    pub fn from_tcb(tcb: &TaskControlBlock, now: usize) -> Self {
+        let time = if tcb.dispatch_count == 0 {
+            0
+        } else {
+            time_delta(now, tcb.init_time) / 1000 // Convert us to ms
+        };
+        TaskInfo {
+            status: tcb.task_status,
+            syscall_times: tcb.syscall_snapshot(),
+            time,
+        }
+    }
+}
+
+/// The stats of a task beyond the lab's [`TaskInfo`], read with `sys_task_stats`
+#[repr(C)]
+pub struct TaskStats {
+    pub avg_dispatch_interval_ms: usize,
+    pub blocked_time_us: usize,
+    pub unknown_syscalls: usize,
+    pub info_overhead_us: usize,
+    pub max_continuous_run_us: usize,
+    pub status_changed_at: usize,
+    pub timer_irqs: usize,
+    pub stats_reset_at: usize,
+    pub exit_reason: ExitReason,
+    pub cpu_share_permille: u32,
+    pub trap_counts: [usize; TRAP_CAUSE_NUM],
+    pub missed_deadlines: usize,
+    pub skipped_count: usize,
+    pub syscall_rate_per_sec: usize,
+    pub dispatch_jitter_us: usize,
+    pub budget_extensions: usize,
+    pub class: TaskClass,
+    pub status_shares: StatusShares,
+    pub syscall_switches: [u32; MAX_SYSCALL_NUM],
+    pub bytes_written: usize,
+}
+
+impl TaskStats {
+    /// The stats of `tcb` at `now`, the one place mapping a task to its `TaskStats`.
+    ///
+    /// `cpu_share_permille` depends on every task, so the caller computes it.
+    pub fn from_tcb(tcb: &TaskControlBlock, now: usize, cpu_share_permille: u32) -> Self {
+        TaskStats {
+            avg_dispatch_interval_ms: tcb.avg_dispatch_interval_ms(),
+            blocked_time_us: tcb.blocked_time_us,
+            unknown_syscalls: tcb.unknown_syscalls,
+            info_overhead_us: tcb.info_overhead_us,
+            max_continuous_run_us: tcb.max_continuous_run_us,
+            status_changed_at: tcb.status_changed_at,
+            timer_irqs: tcb.timer_irqs,
+            stats_reset_at: tcb.stats_reset_at,
+            exit_reason: tcb.exit_reason,
+            cpu_share_permille,
+            trap_counts: tcb.trap_counts,
+            missed_deadlines: tcb.missed_deadlines,
+            skipped_count: tcb.skipped_count,
+            syscall_rate_per_sec: tcb.syscall_rate_per_sec(now),
+            dispatch_jitter_us: tcb.dispatch_jitter_us(),
+            budget_extensions: tcb.budget_extensions,
+            class: tcb.classify(),
+            status_shares: tcb.status_shares(now),
+            syscall_switches: tcb.syscall_switch_snapshot(),
+            bytes_written: tcb.bytes_written,
+        }
+    }
+}
+
+/// Key stats of a finished run of a task, kept across a restart
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct TaskInfoSummary {
+    /// milliseconds from first dispatch to exit
+    pub run_time_ms: usize,
+    pub user_time_us: usize,
+    pub info_overhead_us: usize,
+    pub dispatches: usize,
+    pub syscalls: usize,
+    pub exit_reason: ExitReason,
+}
+
+/// Where the time of a task since its first dispatch went, in microseconds
+///
+/// Read at a single instant, so the parts are consistent with each other.
+/// `kernel_us + user_us` is the time on the CPU, and together with
+/// `ready_wait_us` and `blocked_us` it adds up to `total_us`.
+#[repr(C)]
+pub struct TimeBreakdown {
+    pub total_us: usize,
+    /// on the CPU but not in user mode
+    pub kernel_us: usize,
+    pub user_us: usize,
+    /// `Ready` but waiting for the CPU
+    pub ready_wait_us: usize,
+    /// `Blocked` or `Sleeping`
+    pub blocked_us: usize,
+}
+
+/// How a task's lifetime splits between the statuses, in permille
+///
+/// The three parts add up to 1000, except for a task never dispatched where
+/// all are 0. Running includes time in the kernel on the task's behalf.
+#[repr(C)]
+pub struct StatusShares {
+    pub running_permille: u32,
+    pub ready_permille: u32,
+    /// `Blocked` or `Sleeping`
+    pub blocked_permille: u32,
+}
+
+/// Format version in byte 0 of every task record, bumped on layout changes
+pub const TASK_RECORD_VERSION: u8 = 1;
+
+/// Bytes in a task record written by `encode_task_record`
+///
+/// The record is packed and little-endian, offsets in bytes:
+///
+/// | offset | type | field                                              |
+/// |--------|------|----------------------------------------------------|
+/// | 0      | u8   | format version, [`TASK_RECORD_VERSION`]            |
+/// | 1      | u8   | `TaskStatus` discriminant, as in [`TaskDumpEntry`] |
+/// | 2      | u16  | task id                                            |
+/// | 4      | u32  | priority                                           |
+/// | 8      | u64  | milliseconds since first dispatch, 0 if never      |
+/// | 16     | u64  | user time in microseconds                          |
+/// | 24     | u64  | microseconds charged for building `TaskInfo`       |
+/// | 32     | u64  | dispatches                                         |
+/// | 40     | u64  | syscalls                                           |
+/// | 48     | u64  | yields                                             |
+/// | 56     | u64  | preemptions                                        |
+pub const TASK_RECORD_LEN: usize = 64;
+
+/// One row of the task table dump
+///
+/// `#[repr(C)]` with only `usize` fields: on riscv64 an entry is 48 bytes,
+/// the fields sit at offsets 0, 8, .., 40 in declaration order and entries
+/// follow each other without padding. `status` is the `TaskStatus`
+/// discriminant: 0 UnInit, 1 Ready, 2 Running, 3 Exited, 4 Blocked,
+/// 5 Sleeping, 6 Frozen.
+#[repr(C)]
+pub struct TaskDumpEntry {
+    pub id: usize,
+    pub status: usize,
+    pub priority: usize,
+    /// milliseconds since first dispatch, 0 if never dispatched
+    pub time_ms: usize,
+    pub user_time_us: usize,
+    pub dispatches: usize,
+}
+
+/// Summary of the last few context switch latencies
+#[repr(C)]
+pub struct SwitchLatency {
+    pub samples: usize,
+    pub min_us: usize,
+    pub max_us: usize,
+    pub mean_us: usize,
+}
+
+/// One traced context switch
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct SwitchRecord {
+    pub time_us: usize,
+    /// `usize::MAX` for [`SwitchReason::FirstRun`]
+    pub from_id: usize,
+    pub to_id: usize,
+    pub reason: SwitchReason,
+    /// the rule of the policy that picked `to_id`
+    pub selected_by: SelectReason,
+}
+
+/// The latest context switches, oldest first
+#[repr(C)]
+pub struct SwitchTraceDump {
+    /// records overwritten since boot
+    pub dropped: usize,
+    /// number of valid records in `records`
+    pub len: usize,
+    pub records: [SwitchRecord; SWITCH_TRACE_LEN],
+}
+
+/// One traced syscall
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct TraceEntry {
+    pub time_us: usize,
+    pub syscall_id: usize,
+}
+
+/// The syscall trace of a task, oldest entry first
+#[repr(C)]
+pub struct TraceDump {
+    /// entries overwritten since tracing was enabled
+    pub dropped: usize,
+    /// number of valid entries in `entries`
+    pub len: usize,
+    pub entries: [TraceEntry; TRACE_BUF_LEN],
+}
@@ -9,25 +9,56 @@
 //! Be careful when you see [`__switch`]. Control flow around this function
 //! might not be what you expect.
 
+mod barrier;
 mod context;
 mod info;
 mod switch;
 #[allow(clippy::module_inception)]
 mod task;
 
-use crate::config::{MAX_APP_NUM, MAX_SYSCALL_NUM};
-use crate::loader::{get_num_app, init_app_cx};
+use crate::config::{
+    DEFAULT_TIME_SLICE_MS, MAX_APP_NUM, MAX_BARRIERS, MAX_SCHEDULE_LEN, MAX_SYSCALL_NUM,
+    MIN_TIME_SLICE_MS, STUCK_SELECTION_THRESHOLD, SWITCH_LATENCY_WINDOW, SWITCH_TRACE_LEN,
+    SYSCALL_STORM_PER_SEC,
+};
+use crate::lang_items::{fail, finish};
+use crate::loader::{get_app_descriptors, get_num_app, init_app_cx, reload_app};
 use crate::sync::UPSafeCell;
-use crate::timer::{get_time_us};
-use alloc::collections::BTreeMap;
+use crate::syscall::is_known_syscall;
+use crate::timer::{get_time_us, set_next_trigger, time_delta};
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
 use alloc::{vec, vec::Vec};
+use barrier::Barrier;
+use core::fmt::Write;
 use lazy_static::*;
 pub use switch::__switch;
-pub use task::{TaskControlBlock, TaskStatus};
+pub use task::{
+    clamp_priority, dispatch_jitter_test, AppDescriptor, ExitReason, SelectReason, SwitchReason,
+    TaskClass, TaskControlBlock, TaskStatus, TrapCause,
+};
 
-pub use info::TaskInfo;
+pub use info::{
+    StatusShares, SwitchLatency, SwitchRecord, SwitchTraceDump, TaskDumpEntry, TaskInfo,
+    TaskInfoSummary, TaskStats, TimeBreakdown, TraceDump, TraceEntry, TASK_RECORD_LEN,
+    TASK_RECORD_VERSION,
+};
 pub use context::TaskContext;
 
+/// Check a scheduler invariant, dumping the task table before panicking.
+///
+/// Takes the already borrowed [`TaskManagerInner`] so the failure report
+/// does not try to borrow `TASK_MANAGER` a second time.
+macro_rules! kassert {
+    ($inner:expr, $cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            $inner.print_task_table();
+            panic!($($arg)+);
+        }
+    };
+}
+
 /// The task manager, where all the tasks are managed.
 ///
 /// Functions implemented on `TaskManager` deals with all task state transitions
@@ -42,6 +73,8 @@ pub struct TaskManager {
     num_app: usize,
     /// use inner value to get mutable access
     inner: UPSafeCell<TaskManagerInner>,
+    /// time in microseconds it took to build the task manager
+    init_duration_us: usize,
 }
 
 /// The task manager inner in 'UPSafeCell'
@@ -50,42 +83,341 @@ struct TaskManagerInner {
     tasks: Vec<TaskControlBlock>,
     /// id of current `Running` task
     current_task: usize,
+    /// id of the task that exited last, `usize::MAX` before any exit
+    last_exited_id: usize,
+    /// time in microseconds `last_exited_id` exited at
+    last_exit_time_us: usize,
+    /// barriers configured by `sys_barrier_init`
+    barriers: Vec<Barrier>,
+    /// task to run only when no other task is `Ready`
+    fallback_task: Option<usize>,
+    /// count ids missing from the syscall table as unknown instead of by id
+    validate_syscall_ids: bool,
+    /// syscalls per second of a task above which we warn of a syscall storm, 0 for never
+    syscall_storm_per_sec: usize,
+    /// charge the cost of building `TaskInfo` to the caller's `info_overhead_us`
+    account_self_overhead: bool,
+    /// count syscalls when they are issued, failed ones included; off counts
+    /// only those returning non-negative in `record_syscall`
+    count_failures: bool,
+    /// latencies in microseconds of the last `SWITCH_LATENCY_WINDOW` switches
+    recent_switches: VecDeque<usize>,
+    /// task `sys_switch_to` asked to run next, bypassing the policy
+    forced_next: Option<usize>,
+    /// ids dispatched since `record_schedule`, the first `MAX_SCHEDULE_LEN`
+    /// of them, `None` when not recording
+    recording: Option<Vec<usize>>,
+    /// dispatch order still to be followed by `find_next_task`
+    replay: VecDeque<usize>,
+    /// context switches done by `run_next_task` since boot
+    switch_count: usize,
+    /// stop the kernel once `switch_count` exceeds this, 0 for no limit
+    max_switches: usize,
+    /// dispatches that passed over a runnable task of higher priority
+    priority_inversions: usize,
+    /// microseconds spent in each syscall, summed over all tasks
+    system_syscall_times: [u64; MAX_SYSCALL_NUM],
+    /// syscalls counted by `increase_syscall_count` since boot, over all tasks
+    total_system_syscalls: u64,
+    /// the last `SWITCH_TRACE_LEN` context switches
+    switch_trace: VecDeque<SwitchRecord>,
+    /// switch records overwritten in `switch_trace`
+    switch_trace_dropped: usize,
+    /// microseconds spent in `idle_wait` with nothing to run
+    idle_time_us: usize,
+    /// longest single `idle_wait` in microseconds
+    max_idle_stretch_us: usize,
+    /// `(waiter, target, reason)` of tasks in `sys_waittask` waiting for `target` to exit,
+    /// `reason` is filled in when `target` exits or freezes
+    exit_waiters: Vec<(usize, usize, Option<ExitReason>)>,
+    /// task picked by the last `find_next_task`
+    last_selected: Option<usize>,
+    /// consecutive `find_next_task` calls that picked `last_selected`
+    same_selection_streak: usize,
+    /// microseconds spent in `run_next_task` picking and dispatching, idle time excluded
+    sched_time_us: usize,
+    /// called once every task has exited, before the kernel stops
+    on_all_complete: Option<fn()>,
+    /// time in microseconds of the latest trap into the kernel
+    kernel_entered_at: usize,
+    /// task that took the latest trap, its cause and, for a syscall, the syscall id
+    kernel_entry: (usize, TrapCause, usize),
+    /// warn when the kernel returns to user mode more than this many
+    /// microseconds after the latest trap, `usize::MAX` for never
+    kernel_residency_warn_us: usize,
+    /// let timer ticks preempt tasks, off leaves only voluntary switches
+    preemption_enabled: bool,
+    /// calls of `find_next_task` from `run_next_task`, and the microseconds they took
+    selection_calls: usize,
+    selection_time_us: usize,
+    /// slice length in microseconds for tasks without an explicit `set_time_slice`,
+    /// `None` until set, such tasks are then preempted on every tick
+    global_quantum_us: Option<usize>,
+    /// freeze a task running this many microseconds without giving up the CPU, 0 for never
+    auto_freeze_us: usize,
 }
 
-lazy_static! {
-    /// a `TaskManager` instance through lazy_static!
-    pub static ref TASK_MANAGER: TaskManager = {
-        println!("TASK_MANAGER initializing");
-        let num_app = get_num_app();
-        let mut tasks = vec![TaskControlBlock {
-            task_cx: TaskContext::zero_init(),
-            task_status: TaskStatus::UnInit,
-            syscall_times: BTreeMap::new(),
-            init_time: 0,
-        }; MAX_APP_NUM];
+/// Outcome of a scheduling decision in [`TaskManager::find_next_task`]
+enum NextTask {
+    /// run this task, picked by this rule
+    Run(usize, SelectReason),
+    /// every loaded task has exited or is frozen
+    AllExited,
+    /// tasks remain but none is runnable; `earliest_wake` is the soonest
+    /// `wake_at` of a sleeper, `None` if every one is `Blocked`
+    AllBlocked { earliest_wake: Option<usize> },
+}
+
+impl TaskManagerInner {
+    /// The state right after boot: apps configured from `descriptors`, all `Ready`.
+    ///
+    /// Writes each app's initial trap context onto its kernel stack.
+    fn new(num_app: usize, descriptors: &[AppDescriptor]) -> Self {
+        let mut tasks = vec![TaskControlBlock::uninit(); MAX_APP_NUM];
         println!("task block size: {}", core::mem::size_of_val(&tasks));
         println!("tasks prepared, all UnInit");
         for (i, t) in tasks.iter_mut().enumerate().take(num_app) {
             println!("task #{} gets ready", i);
+            let desc = descriptors.get(i).copied().unwrap_or_default();
+            t.set_priority(desc.priority);
+            t.weight = desc.weight;
+            t.tag = desc.tag;
             t.task_cx = TaskContext::goto_restore(init_app_cx(i));
-            t.task_status = TaskStatus::Ready;
+            t.set_status(TaskStatus::Ready, get_time_us());
         }
         println!("tasks initialized, build TASK_MANAGER");
-        let inner = unsafe { UPSafeCell::new(TaskManagerInner {
+        TaskManagerInner {
             tasks,
             current_task: 0,
-        })};
+            last_exited_id: usize::MAX,
+            last_exit_time_us: 0,
+            barriers: Vec::new(),
+            fallback_task: None,
+            validate_syscall_ids: false,
+            syscall_storm_per_sec: SYSCALL_STORM_PER_SEC,
+            account_self_overhead: false,
+            count_failures: true,
+            recent_switches: VecDeque::new(),
+            forced_next: None,
+            recording: None,
+            replay: VecDeque::new(),
+            switch_count: 0,
+            max_switches: 0,
+            priority_inversions: 0,
+            system_syscall_times: [0; MAX_SYSCALL_NUM],
+            total_system_syscalls: 0,
+            switch_trace: VecDeque::new(),
+            switch_trace_dropped: 0,
+            idle_time_us: 0,
+            max_idle_stretch_us: 0,
+            exit_waiters: Vec::new(),
+            last_selected: None,
+            same_selection_streak: 0,
+            sched_time_us: 0,
+            on_all_complete: None,
+            kernel_entered_at: 0,
+            kernel_entry: (0, TrapCause::Other, 0),
+            kernel_residency_warn_us: usize::MAX,
+            preemption_enabled: true,
+            selection_calls: 0,
+            selection_time_us: 0,
+            global_quantum_us: None,
+            auto_freeze_us: 0,
+        }
+    }
+
+    /// Append a switch to the trace, overwriting the oldest record once full.
+    fn trace_switch(
+        &mut self,
+        from_id: usize,
+        to_id: usize,
+        reason: SwitchReason,
+        selected_by: SelectReason,
+    ) {
+        if self.switch_trace.len() == SWITCH_TRACE_LEN {
+            self.switch_trace.pop_front();
+            self.switch_trace_dropped += 1;
+        }
+        self.switch_trace.push_back(SwitchRecord {
+            time_us: get_time_us(),
+            from_id,
+            to_id,
+            reason,
+            selected_by,
+        });
+    }
+
+    /// The status of every slot, `UnInit` ones included.
+    fn statuses(&self) -> [TaskStatus; MAX_APP_NUM] {
+        let mut statuses = [TaskStatus::UnInit; MAX_APP_NUM];
+        for (status, task) in statuses.iter_mut().zip(self.tasks.iter()) {
+            *status = task.task_status;
+        }
+        statuses
+    }
+
+    /// Stop everybody waiting on task `id`, which will never run again, at `now`.
+    ///
+    /// Barriers `id` joined stop waiting for it, see [`Barrier::leave`], and
+    /// tasks waiting for it to exit wake with its exit reason recorded, so a
+    /// restart of `id` before they run cannot change what they see. Both the
+    /// exit and the freeze path end here.
+    fn release_waiters(&mut self, id: usize, now: usize) {
+        for barrier in self.barriers.iter_mut() {
+            if !barrier.leave(id) {
+                continue;
+            }
+            if !barrier.waiters.is_empty() && barrier.is_full() {
+                for waiter in barrier.release() {
+                    self.tasks[waiter].wake(now);
+                }
+            }
+        }
+        let reason = self.tasks[id].exit_reason;
+        for (waiter, target, result) in self.exit_waiters.iter_mut() {
+            if *target != id || result.is_some() {
+                continue;
+            }
+            *result = Some(reason);
+            let task = &mut self.tasks[*waiter];
+            // a waiter whose timeout already woke it is `Ready` and only needs the reason
+            if !task.task_status.is_runnable() {
+                task.wake(now);
+            }
+        }
+    }
+
+    /// Slice length of tasks without an explicit slice, `DEFAULT_TIME_SLICE_MS` until set.
+    fn quantum_us(&self) -> usize {
+        self.global_quantum_us.unwrap_or(DEFAULT_TIME_SLICE_MS * 1000)
+    }
+
+    /// User time of task `id` in permille of the user time of all tasks.
+    ///
+    /// 0 before any user time was charged.
+    fn cpu_share_permille(&self, id: usize) -> u32 {
+        let total: usize = self.tasks.iter().map(|t| t.user_time).sum();
+        if total == 0 {
+            return 0;
+        }
+        (self.tasks[id].user_time * 1000 / total) as u32
+    }
+
+    /// Check the scheduler invariants at the end of a scheduling decision.
+    ///
+    /// Only debug builds pay for this: without `debug_assertions` the body
+    /// folds away. There is no ready queue yet, so we check that exactly one
+    /// task is `Running` and that it is `current_task`.
+    fn check_invariants(&self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        let running = self
+            .tasks
+            .iter()
+            .filter(|t| t.task_status == TaskStatus::Running)
+            .count();
+        kassert!(
+            self,
+            running == 1,
+            "scheduler invariant: {} tasks are Running",
+            running
+        );
+        kassert!(
+            self,
+            self.tasks[self.current_task].task_status == TaskStatus::Running,
+            "scheduler invariant: current task {} is not Running",
+            self.current_task
+        );
+    }
+
+    /// The earliest `wake_at` of any `Sleeping` task, `None` if none sleeps.
+    fn next_wake_time_us(&self) -> Option<usize> {
+        self.tasks
+            .iter()
+            .filter(|t| t.task_status == TaskStatus::Sleeping)
+            .map(|t| t.wake_at)
+            .min()
+    }
+
+    /// Count syscall `syscall_id` for the current task.
+    ///
+    /// Besides the per-id and total counts this feeds the trace and the
+    /// syscall storm check. A task with `counting_enabled` off is skipped.
+    /// An id too large for the `u16` keys of the sparse counts only bumps
+    /// the unknown-syscall bucket, it would alias a real id otherwise.
+    fn count_syscall(&mut self, syscall_id: usize) {
+        let current = self.current_task;
+        if !self.tasks[current].counting_enabled {
+            return;
+        }
+        if syscall_id > u16::MAX as usize {
+            self.tasks[current].unknown_syscalls += 1;
+            return;
+        }
+        let syscall_id = syscall_id as u16;
+        let val = self.tasks[current].syscall_times.entry(syscall_id).or_insert(0);
+        *val += 1;
+        self.tasks[current].total_syscalls += 1;
+        self.total_system_syscalls += 1;
+        let now = get_time_us();
+        if self.tasks[current].trace_enabled {
+            self.tasks[current].trace_syscall(now, syscall_id as usize);
+        }
+        let threshold = self.syscall_storm_per_sec;
+        let task = &mut self.tasks[current];
+        task.note_syscall_time(now);
+        let rate = task.syscall_rate_per_sec(now);
+        let storm = threshold != 0 && rate > threshold;
+        if storm && !task.in_syscall_storm {
+            warn!(
+                "[kernel] task {} issues {} syscalls/s, above the storm threshold of {}",
+                current, rate, threshold
+            );
+        }
+        task.in_syscall_storm = storm;
+    }
+
+    /// Print the current task id and one line per loaded task, for [`kassert`] failures.
+    fn print_task_table(&self) {
+        println!("[kernel] current task: {}", self.current_task);
+        for (id, task) in self.tasks.iter().enumerate() {
+            if task.task_status == TaskStatus::UnInit {
+                continue;
+            }
+            println!(
+                "[kernel]   task {}: {:?}, priority {}, {} dispatches, {} syscalls",
+                id, task.task_status, task.priority, task.dispatch_count, task.total_syscalls
+            );
+        }
+    }
+}
+
+lazy_static! {
+    /// a `TaskManager` instance through lazy_static!
+    pub static ref TASK_MANAGER: TaskManager = TaskManager::from_descriptors(get_app_descriptors());
+}
+
+impl TaskManager {
+    /// Build the task table, configuring app `i` from `descriptors[i]`.
+    ///
+    /// Apps without a descriptor, or every app when `descriptors` is empty,
+    /// get [`AppDescriptor::default`].
+    pub fn from_descriptors(descriptors: &[AppDescriptor]) -> Self {
+        let init_start = get_time_us();
+        println!("TASK_MANAGER initializing");
+        let num_app = get_num_app();
+        let inner = unsafe { UPSafeCell::new(TaskManagerInner::new(num_app, descriptors)) };
         println!("TASK_MANAGER inner built");
         let task_manager = TaskManager {
             num_app,
             inner,
+            init_duration_us: time_delta(get_time_us(), init_start),
         };
         println!("TASK_MANAGER built, return");
         task_manager
-    };
-}
+    }
 
-impl TaskManager {
     /// Run the first task in task list.
     ///
     /// Generally, the first task in task list is an idle task (we call it zero process later).
@@ -94,9 +426,10 @@ impl TaskManager {
         println!("TaskManager::run_first_task start");
         let mut inner = self.inner.exclusive_access();
         let task0 = &mut inner.tasks[0];
-        task0.task_status = TaskStatus::Running;
-        task0.init_time = get_time_us();
+        task0.dispatch(get_time_us());
         let next_task_cx_ptr = &task0.task_cx as *const TaskContext;
+        inner.trace_switch(usize::MAX, 0, SwitchReason::FirstRun, SelectReason::Initial);
+        inner.check_invariants();
         drop(inner);
         let mut _unused = TaskContext::zero_init();
         // before this, we should drop local variables that must be dropped manually
@@ -106,119 +439,2185 @@ impl TaskManager {
         panic!("unreachable in run_first_task!");
     }
 
+    /// Put every task back to its state right after boot and run task 0 again.
+    ///
+    /// Apps are reloaded and `inner` is rebuilt by [`TaskManagerInner::new`],
+    /// keeping only the completion hook, which is kernel configuration. This
+    /// also rebuilds every trap context, the caller's included. This is only
+    /// safe because the caller never resumes: its trap context sits above the
+    /// frames we run on and `run_first_task` leaves this stack for good. The
+    /// borrow of `inner` therefore ends before the switch. The old task table
+    /// is freed before the new one is built, so the heap never holds both.
+    fn soft_reset(&self) -> ! {
+        for i in 0..self.num_app {
+            reload_app(i);
+        }
+        let mut inner = self.inner.exclusive_access();
+        let on_all_complete = inner.on_all_complete;
+        inner.tasks = Vec::new();
+        drop(inner);
+        let mut fresh = TaskManagerInner::new(self.num_app, get_app_descriptors());
+        fresh.on_all_complete = on_all_complete;
+        *self.inner.exclusive_access() = fresh;
+        self.run_first_task()
+    }
+
     /// Change the status of current `Running` task into `Ready`.
     fn mark_current_suspended(&self) {
         let mut inner = self.inner.exclusive_access();
         let current = inner.current_task;
-        inner.tasks[current].task_status = TaskStatus::Ready;
+        inner.tasks[current].set_status(TaskStatus::Ready, get_time_us());
+    }
+
+    /// Change the status of current `Running` task into `Exited` for `reason`.
+    fn mark_current_exited(&self, reason: ExitReason) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        kassert!(
+            inner,
+            inner.tasks[current].task_status == TaskStatus::Running,
+            "exiting task {} is {:?}, not Running",
+            current,
+            inner.tasks[current].task_status
+        );
+        let now = get_time_us();
+        inner.tasks[current].set_status(TaskStatus::Exited, now);
+        inner.tasks[current].exit_reason = reason;
+        inner.tasks[current].exit_time_us = now;
+        inner.last_exited_id = current;
+        inner.last_exit_time_us = now;
+        inner.release_waiters(current, now);
     }
 
-    /// Change the status of current `Running` task into `Exited`.
-    fn mark_current_exited(&self) {
+    /// Run `Exited` task `id` again from a freshly loaded image.
+    ///
+    /// Priority, weight, tag, name, slice, share and budget carry over, every
+    /// counter starts from zero and the finished run is kept in `prev_run`.
+    /// The time the slot sat exited is kept in `slot_idle_us`. The caller and
+    /// `id` become related, see [`Self::list_related`], and relations of the
+    /// slot survive the restart.
+    /// Returns `false` if `id` is out of range or has not exited.
+    fn restart_task(&self, id: usize) -> bool {
+        if id >= self.num_app {
+            return false;
+        }
         let mut inner = self.inner.exclusive_access();
+        let old = &inner.tasks[id];
+        if old.task_status != TaskStatus::Exited {
+            return false;
+        }
+        let mut task = TaskControlBlock::uninit();
+        task.priority = old.priority;
+        task.weight = old.weight;
+        task.tag = old.tag;
+        task.name = old.name;
+        task.name_len = old.name_len;
+        task.time_slice = old.time_slice;
+        task.explicit_slice = old.explicit_slice;
+        task.min_share_permille = old.min_share_permille;
+        task.cpu_budget_us = old.cpu_budget_us;
+        task.prev_run = Some(old.summary());
+        task.related = old.related.clone();
         let current = inner.current_task;
-        inner.tasks[current].task_status = TaskStatus::Exited;
+        task.relate(current);
+        let now = get_time_us();
+        task.slot_idle_us = time_delta(now, old.exit_time_us);
+        reload_app(id);
+        task.task_cx = TaskContext::goto_restore(init_app_cx(id));
+        task.set_status(TaskStatus::Ready, now);
+        inner.tasks[id] = task;
+        inner.tasks[current].relate(id);
+        true
+    }
+
+    /// Ids of the tasks that restarted slot `id` or were restarted by it, in id order.
+    ///
+    /// This is the whole lineage of the slot since boot. Returns `None` if
+    /// `id` is out of range, an empty list if it has no relations.
+    fn list_related(&self, id: usize) -> Option<Vec<usize>> {
+        if id >= self.num_app {
+            return None;
+        }
+        Some(self.inner.exclusive_access().tasks[id].related.clone())
     }
 
-    /// Find next task to run and return task id.
+    /// Microseconds slot `id` sat exited before its last restart.
     ///
-    /// In this case, we only return the first `Ready` task in task list.
-    fn find_next_task(&self) -> Option<usize> {
+    /// Returns `None` if `id` is out of range or was never restarted.
+    fn get_slot_idle_us(&self, id: usize) -> Option<usize> {
+        if id >= self.num_app {
+            return None;
+        }
         let inner = self.inner.exclusive_access();
+        let task = &inner.tasks[id];
+        task.prev_run.map(|_| task.slot_idle_us)
+    }
+
+    /// Key stats of the run of task `id` before its last restart.
+    ///
+    /// Returns `None` if `id` is out of range or was never restarted.
+    fn get_prev_run_info(&self, id: usize) -> Option<TaskInfoSummary> {
+        if id >= self.num_app {
+            return None;
+        }
+        self.inner.exclusive_access().tasks[id].prev_run
+    }
+
+    /// Make the current task wait for task `id` to exit, for at most `timeout_ms`.
+    ///
+    /// The caller is `Sleeping` until its timeout, or `Blocked` with a
+    /// `timeout_ms` of 0, and is woken by whichever comes first. Returns
+    /// `None` if `id` is out of range or the caller itself, `Some(false)` if
+    /// `id` has already exited or is frozen and there is nothing to wait for.
+    fn begin_wait_task(&self, id: usize, timeout_ms: usize) -> Option<bool> {
+        let mut inner = self.inner.exclusive_access();
         let current = inner.current_task;
-        (current + 1..current + self.num_app + 1)
-            .map(|id| id % self.num_app)
-            .find(|id| inner.tasks[*id].task_status == TaskStatus::Ready)
+        if id >= self.num_app || id == current {
+            return None;
+        }
+        if inner.tasks[id].task_status.is_terminal() {
+            return Some(false);
+        }
+        inner.exit_waiters.push((current, id, None));
+        let task = &mut inner.tasks[current];
+        if timeout_ms == 0 {
+            task.block(TaskStatus::Blocked, get_time_us());
+        } else {
+            task.wake_at = get_time_us().saturating_add(timeout_ms.saturating_mul(1000));
+            task.block(TaskStatus::Sleeping, get_time_us());
+        }
+        Some(true)
     }
 
-    /// Switch current `Running` task to the task we have found,
-    /// or there is no `Ready` task and we can exit with all applications completed
-    fn run_next_task(&self) {
-        if let Some(next) = self.find_next_task() {
-            let mut inner = self.inner.exclusive_access();
-            let current = inner.current_task;
-            inner.tasks[next].task_status = TaskStatus::Running;
-            if inner.tasks[next].init_time == 0 {
-                inner.tasks[next].init_time = get_time_us();
-            }
-            inner.current_task = next;
-            let current_task_cx_ptr = &mut inner.tasks[current].task_cx as *mut TaskContext;
-            let next_task_cx_ptr = &inner.tasks[next].task_cx as *const TaskContext;
-            drop(inner);
-            // before this, we should drop local variables that must be dropped manually
-            unsafe {
-                __switch(current_task_cx_ptr, next_task_cx_ptr);
+    /// Drop the current task's wait, returning the exit reason recorded for its target.
+    ///
+    /// `ExitReason::None` means the target neither exited nor froze before
+    /// the timeout woke the caller.
+    fn end_wait_task(&self) -> ExitReason {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        let pos = inner
+            .exit_waiters
+            .iter()
+            .position(|&(waiter, _, _)| waiter == current);
+        match pos {
+            Some(pos) => inner.exit_waiters.remove(pos).2.unwrap_or(ExitReason::None),
+            None => ExitReason::None,
+        }
+    }
+
+    /// Why task `id` exited or froze, `ExitReason::None` while it has not.
+    ///
+    /// Returns `None` if `id` is out of range.
+    fn get_exit_reason(&self, id: usize) -> Option<ExitReason> {
+        if id >= self.num_app {
+            return None;
+        }
+        Some(self.inner.exclusive_access().tasks[id].exit_reason)
+    }
+
+    /// Configure barrier `id` to release once `count` tasks have arrived.
+    ///
+    /// A member exiting lowers the count for good, see [`Barrier::leave`].
+    /// Returns `false` if `count` is 0, tasks are still waiting on `id`, or
+    /// `id` is new and `MAX_BARRIERS` barriers are configured already.
+    fn barrier_init(&self, id: usize, count: usize) -> bool {
+        if count == 0 {
+            return false;
+        }
+        let mut inner = self.inner.exclusive_access();
+        let no_room = inner.barriers.len() == MAX_BARRIERS;
+        match inner.barriers.iter_mut().find(|b| b.id == id) {
+            Some(barrier) if !barrier.waiters.is_empty() => return false,
+            Some(barrier) => *barrier = Barrier::new(id, count),
+            None if no_room => return false,
+            None => inner.barriers.push(Barrier::new(id, count)),
+        }
+        true
+    }
+
+    /// Arrive at barrier `id` as the current task.
+    ///
+    /// The last arrival wakes every waiter and starts a new round. Any other
+    /// arrival marks the current task `Blocked` and returns `Some(true)`, the
+    /// caller has to switch away. Returns `None` if `id` is not configured or
+    /// too few live tasks are left to ever fill this round.
+    fn barrier_arrive(&self, id: usize) -> Option<bool> {
+        let mut inner = self.inner.exclusive_access();
+        let inner = &mut *inner;
+        let current = inner.current_task;
+        let barrier = inner.barriers.iter_mut().find(|b| b.id == id)?;
+        let may_arrive = inner.tasks[..self.num_app]
+            .iter()
+            .enumerate()
+            .filter(|&(i, t)| {
+                i != current && !t.task_status.is_terminal() && !barrier.waiters.contains(&i)
+            })
+            .count();
+        if barrier.arrived + 1 + may_arrive < barrier.required {
+            return None;
+        }
+        barrier.arrive(current);
+        if !barrier.is_full() {
+            barrier.waiters.push(current);
+            inner.tasks[current].block(TaskStatus::Blocked, get_time_us());
+            return Some(true);
+        }
+        let now = get_time_us();
+        for waiter in barrier.release() {
+            inner.tasks[waiter].wake(now);
+        }
+        Some(false)
+    }
+
+    /// Put the current task to sleep for `us` microseconds.
+    fn mark_current_sleeping(&self, us: usize) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        let task = &mut inner.tasks[current];
+        let now = get_time_us();
+        task.wake_at = now.saturating_add(us);
+        task.block(TaskStatus::Sleeping, now);
+    }
+
+    /// Put the current task to sleep until `get_time_us()` reaches `wake_at`.
+    ///
+    /// Returns `false` without blocking if `wake_at` has already passed.
+    fn mark_current_sleeping_until(&self, wake_at: usize) -> bool {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        let task = &mut inner.tasks[current];
+        let now = get_time_us();
+        if wake_at <= now {
+            return false;
+        }
+        task.wake_at = wake_at;
+        task.block(TaskStatus::Sleeping, now);
+        true
+    }
+
+    /// Make every sleeping task whose `wake_at` has passed `Ready`.
+    ///
+    /// Blocked time is charged up to `wake_at`, not to whenever we noticed.
+    fn wake_sleepers(&self) {
+        let mut inner = self.inner.exclusive_access();
+        let now = get_time_us();
+        for task in inner.tasks.iter_mut().take(self.num_app) {
+            if task.task_status == TaskStatus::Sleeping && task.wake_at <= now {
+                task.wake(task.wake_at);
             }
-            // go back to user mode
-        } else {
-            panic!("All applications completed!");
         }
     }
 
-    // LAB1: Try to implement your function to update or get task info!
-    fn increase_syscall_count(&self, syscall_id: u16) {
+    /// Id and exit time of the task that exited last, id is `usize::MAX` before any exit.
+    fn get_last_exited(&self) -> (usize, usize) {
+        let inner = self.inner.exclusive_access();
+        (inner.last_exited_id, inner.last_exit_time_us)
+    }
+
+    /// Count a trap of the current task with `cause`.
+    fn count_current_trap(&self, cause: TrapCause) {
         let mut inner = self.inner.exclusive_access();
         let current = inner.current_task;
-        let val = inner.tasks[current].syscall_times.entry(syscall_id).or_insert(0);
-        *val += 1;
+        inner.tasks[current].trap_counts[cause as usize] += 1;
+        inner.kernel_entry = (current, cause, 0);
     }
 
-    fn get_current_task_info(&self) -> TaskInfo {
+    /// Times the current task entered the kernel, every trap cause added up.
+    ///
+    /// Counts the traps seen by [`Self::count_current_trap`], so the
+    /// syscall asking for this is already included.
+    fn get_current_kernel_entries(&self) -> usize {
         let inner = self.inner.exclusive_access();
+        inner.tasks[inner.current_task].trap_counts.iter().sum()
+    }
+
+    /// Boost the current task for a critical region, see [`TaskControlBlock::boost_begin`].
+    fn priority_boost_begin(&self) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        inner.tasks[current].boost_begin();
+    }
+
+    /// End a critical region of the current task, see [`TaskControlBlock::boost_end`].
+    fn priority_boost_end(&self) -> bool {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        inner.tasks[current].boost_end()
+    }
+
+    /// Count a voluntary yield of the current task.
+    fn count_current_yield(&self) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        inner.tasks[current].yield_count += 1;
+    }
+
+    /// Count a timer preemption of the current task.
+    fn count_current_preempt(&self) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        inner.tasks[current].preempt_count += 1;
+    }
+
+    /// Count a timer tick against the current task and charge its slice.
+    ///
+    /// Returns `true` if the task should be preempted, never while preemption
+    /// is disabled. A task with a slice set by [`Self::set_time_slice`], or
+    /// any task once a global quantum is set, runs until the slice is used
+    /// up. Otherwise the task is preempted on every tick.
+    fn tick_current_slice(&self) -> bool {
+        let mut inner = self.inner.exclusive_access();
         let current = inner.current_task;
-        let mut count = [0u32; MAX_SYSCALL_NUM];
-        for (key, val) in inner.tasks[current].syscall_times.iter() {
-            count[*key as usize] = *val;
+        let preemption_enabled = inner.preemption_enabled;
+        let quantum_set = inner.global_quantum_us.is_some();
+        let task = &mut inner.tasks[current];
+        task.timer_irqs += 1;
+        task.charge_slice(get_time_us());
+        let slice_gated = task.explicit_slice || quantum_set;
+        preemption_enabled && (!slice_gated || task.remaining_slice() == 0)
+    }
+
+    /// Let timer ticks preempt tasks (the default), or only switch on yields and blocking.
+    ///
+    /// Timer ticks keep charging slices either way.
+    fn set_preemption_enabled(&self, enabled: bool) {
+        self.inner.exclusive_access().preemption_enabled = enabled;
+    }
+
+    /// Set the slice length of tasks without an explicit slice to `ms` milliseconds.
+    ///
+    /// From then on timer ticks preempt such tasks only once their slice is
+    /// used up. The new length applies from the next slice a task starts,
+    /// the one under way is charged at the length it started with. Returns
+    /// `false` if `ms` is below `MIN_TIME_SLICE_MS`.
+    fn set_global_quantum_ms(&self, ms: usize) -> bool {
+        if ms < MIN_TIME_SLICE_MS {
+            return false;
         }
-        let time = (get_time_us() - inner.tasks[current].init_time) / 1000; // Convert us to ms
-        TaskInfo {
-            status: inner.tasks[current].task_status,
-            syscall_times: count,
-            time,
+        self.inner.exclusive_access().global_quantum_us = Some(ms.saturating_mul(1000));
+        true
+    }
+
+    /// The slice length in milliseconds of tasks without an explicit slice.
+    fn get_global_quantum_ms(&self) -> usize {
+        self.inner.exclusive_access().quantum_us() / 1000
+    }
+
+    /// Whether the current task has used up its CPU budget.
+    fn is_current_over_budget(&self) -> bool {
+        let inner = self.inner.exclusive_access();
+        inner.tasks[inner.current_task].is_over_budget()
+    }
+
+    /// Freeze tasks that run `ms` milliseconds without giving up the CPU, 0 turns it off.
+    fn set_auto_freeze_threshold(&self, ms: usize) {
+        self.inner.exclusive_access().auto_freeze_us = ms.saturating_mul(1000);
+    }
+
+    /// Whether the current task has run past the auto-freeze threshold
+    /// since it last yielded or blocked, see [`TaskControlBlock::busy_time_us`].
+    fn is_current_runaway(&self) -> bool {
+        let inner = self.inner.exclusive_access();
+        let task = &inner.tasks[inner.current_task];
+        inner.auto_freeze_us != 0 && task.busy_time_us(get_time_us()) >= inner.auto_freeze_us
+    }
+
+    /// Change the status of current `Running` task into `Frozen`.
+    ///
+    /// A frozen task never runs again, so whoever waits on it is released
+    /// just as on an exit.
+    fn mark_current_frozen(&self) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        let now = get_time_us();
+        warn!(
+            "[kernel] task {} ran {} us without giving up the CPU, frozen.",
+            current,
+            inner.tasks[current].busy_time_us(now)
+        );
+        inner.tasks[current].set_status(TaskStatus::Frozen, now);
+        inner.tasks[current].exit_reason = ExitReason::Frozen;
+        inner.tasks[current].exit_time_us = now;
+        inner.release_waiters(current, now);
+    }
+
+    /// Limit task `id` to `us` microseconds of user time, 0 for no limit.
+    ///
+    /// The budget is checked on timer ticks, so a task may overrun it by up
+    /// to one tick before it is reaped. Returns `false` if `id` is out of range.
+    fn set_cpu_budget(&self, id: usize, us: usize) -> bool {
+        if id >= self.num_app {
+            return false;
         }
+        self.inner.exclusive_access().tasks[id].cpu_budget_us = us;
+        true
     }
-}
 
-/// Run the first task in task list.
-pub fn run_first_task() {
-    println!("run_first_task start");
-    TASK_MANAGER.run_first_task();
-}
+    /// Grant task `id` another `ms` milliseconds of user time on top of its CPU budget.
+    ///
+    /// Each extension is counted in `budget_extensions`. Returns `false` if
+    /// `id` is out of range or has no budget to extend.
+    fn extend_budget(&self, id: usize, ms: usize) -> bool {
+        if id >= self.num_app {
+            return false;
+        }
+        let mut inner = self.inner.exclusive_access();
+        let task = &mut inner.tasks[id];
+        if task.cpu_budget_us == 0 {
+            return false;
+        }
+        task.cpu_budget_us = task.cpu_budget_us.saturating_add(ms.saturating_mul(1000));
+        task.budget_extensions += 1;
+        true
+    }
 
-/// Switch current `Running` task to the task we have found,
-/// or there is no `Ready` task and we can exit with all applications completed
-fn run_next_task() {
-    TASK_MANAGER.run_next_task();
-}
+    /// Microseconds left in the current task's time slice.
+    fn get_remaining_slice_us(&self) -> usize {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        let task = &mut inner.tasks[current];
+        task.charge_slice(get_time_us());
+        task.remaining_slice()
+    }
 
-/// Change the status of current `Running` task into `Ready`.
-fn mark_current_suspended() {
-    TASK_MANAGER.mark_current_suspended();
-}
+    /// Set the time slice of task `id` to `ms` milliseconds.
+    ///
+    /// The new length applies to the quantum already under way: the time used
+    /// so far is charged first and kept. Returns `None` if `id` is out of
+    /// range or `ms` is below `MIN_TIME_SLICE_MS`, else whether `id` is the
+    /// current task, has already used up the new slice and preemption is on.
+    fn set_time_slice(&self, id: usize, ms: usize) -> Option<bool> {
+        if id >= self.num_app || ms < MIN_TIME_SLICE_MS {
+            return None;
+        }
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        let preemption_enabled = inner.preemption_enabled;
+        let task = &mut inner.tasks[id];
+        task.explicit_slice = true;
+        if id != current {
+            task.time_slice = ms.saturating_mul(1000);
+            return Some(false);
+        }
+        // charge at the old length, `charge_slice` clamps to whatever `time_slice` is
+        task.charge_slice(get_time_us());
+        let used = task.slice_used;
+        task.time_slice = ms.saturating_mul(1000);
+        task.slice_used = used.min(task.time_slice);
+        Some(preemption_enabled && task.remaining_slice() == 0)
+    }
 
-/// Change the status of current `Running` task into `Exited`.
-fn mark_current_exited() {
-    TASK_MANAGER.mark_current_exited();
-}
+    /// Find next task to run and return task id with the rule that picked it.
+    ///
+    /// See [`Self::select_next`] for the policy. A pending
+    /// [`Self::request_switch_to`] is used up even if its task is no longer
+    /// runnable, and so is the replayed id unless `sys_switch_to` won.
+    /// Every decision first counts the deadlines that have passed, and bumps
+    /// `skipped_count` of each `Ready` task it did not pick. With nothing to
+    /// run, the result tells apart finished and waiting tasks.
+    fn find_next_task(&self) -> NextTask {
+        let mut inner = self.inner.exclusive_access();
+        let now = get_time_us();
+        for task in inner.tasks.iter_mut().take(self.num_app) {
+            task.check_deadline(now);
+        }
+        let next = self.select_next(&inner);
+        inner.forced_next = None;
+        if !matches!(next, Some((_, SelectReason::Directed))) {
+            inner.replay.pop_front();
+        }
+        if let Some((id, selected_by)) = next {
+            // every other `Ready` task was passed over by this decision
+            for (other, task) in inner.tasks[..self.num_app].iter_mut().enumerate() {
+                if other != id && task.task_status.is_runnable() {
+                    task.skipped_count += 1;
+                }
+            }
+            self.check_stuck_selection(&mut inner, id);
+            return NextTask::Run(id, selected_by);
+        }
+        if inner.tasks[..self.num_app]
+            .iter()
+            .all(|t| t.task_status.is_terminal())
+        {
+            return NextTask::AllExited;
+        }
+        NextTask::AllBlocked {
+            earliest_wake: inner.next_wake_time_us(),
+        }
+    }
 
-/// Suspend the current 'Running' task and run the next task in task list.
-pub fn suspend_current_and_run_next() {
-    mark_current_suspended();
-    run_next_task();
-}
+    /// Warn when task `id` keeps being picked while other tasks are `Ready`.
+    ///
+    /// Every `STUCK_SELECTION_THRESHOLD` consecutive picks of the same task
+    /// log once, but only if some other task is `Ready` at that point. A
+    /// different pick starts a new streak.
+    fn check_stuck_selection(&self, inner: &mut TaskManagerInner, id: usize) {
+        if inner.last_selected != Some(id) {
+            inner.last_selected = Some(id);
+            inner.same_selection_streak = 1;
+            return;
+        }
+        inner.same_selection_streak += 1;
+        if inner.same_selection_streak % STUCK_SELECTION_THRESHOLD != 0 {
+            return;
+        }
+        let skipped = inner.tasks[..self.num_app]
+            .iter()
+            .enumerate()
+            .filter(|(i, t)| *i != id && t.task_status.is_runnable())
+            .count();
+        if skipped > 0 {
+            warn!(
+                "[kernel] scheduler picked task {} {} times in a row, skipping {} ready tasks",
+                id, inner.same_selection_streak, skipped
+            );
+        }
+    }
 
-/// Exit the current 'Running' task and run the next task in task list.
-pub fn exit_current_and_run_next() {
-    mark_current_exited();
-    run_next_task();
-}
+    /// Pick the next task without changing any state.
+    ///
+    /// In this case, we only return the first runnable task in task list, see
+    /// [`TaskStatus::is_runnable`]. The fallback task is skipped there and
+    /// only picked, if runnable, when no other task is. A task requested by
+    /// [`Self::request_switch_to`] beats all of this, once. Next come the
+    /// ids of a schedule being replayed, an id that is not runnable when its
+    /// turn comes is skipped in favor of the normal policy. Then a task
+    /// below its guaranteed share, see [`Self::most_starved`], and then the
+    /// task with the nearest deadline, see [`Self::earliest_deadline`]. The
+    /// id comes with the rule that picked it.
+    fn select_next(&self, inner: &TaskManagerInner) -> Option<(usize, SelectReason)> {
+        self.select_from(
+            inner,
+            &inner.statuses(),
+            inner.current_task,
+            inner.forced_next,
+            inner.replay.front().copied(),
+        )
+    }
 
-// LAB1: Public functions implemented here provide interfaces.
-// You may use TASK_MANAGER member functions to handle requests.
-pub fn increase_syscall_count(syscall_id: u16) {
-    TASK_MANAGER.increase_syscall_count(syscall_id);
-}
+    /// [`Self::select_next`] with the state a pick moves on passed in.
+    ///
+    /// `statuses`, `current`, `forced_next` and `replay_next` stand in for
+    /// the task statuses, the current task, `forced_next` and the front of
+    /// `replay`, the only state a pick changes that the next pick depends
+    /// on. That lets [`Self::predict_schedule`] step through picks without
+    /// touching `inner`.
+    fn select_from(
+        &self,
+        inner: &TaskManagerInner,
+        statuses: &[TaskStatus; MAX_APP_NUM],
+        current: usize,
+        forced_next: Option<usize>,
+        replay_next: Option<usize>,
+    ) -> Option<(usize, SelectReason)> {
+        if let Some(id) = forced_next {
+            if statuses[id].is_runnable() {
+                return Some((id, SelectReason::Directed));
+            }
+        }
+        if let Some(id) = replay_next {
+            if statuses[id].is_runnable() {
+                return Some((id, SelectReason::Replay));
+            }
+        }
+        if let Some(id) = self.most_starved(inner, statuses) {
+            return Some((id, SelectReason::MinShare));
+        }
+        if let Some(id) = self.earliest_deadline(inner, statuses) {
+            return Some((id, SelectReason::EarliestDeadline));
+        }
+        let fallback = inner.fallback_task;
+        (current + 1..current + self.num_app + 1)
+            .map(|id| id % self.num_app)
+            .find(|id| Some(*id) != fallback && statuses[*id].is_runnable())
+            .map(|id| (id, SelectReason::RoundRobin))
+            .or_else(|| {
+                fallback
+                    .filter(|id| statuses[*id].is_runnable())
+                    .map(|id| (id, SelectReason::Fallback))
+            })
+    }
+
+    /// The next `n` tasks the policy would dispatch if no task changed state,
+    /// at most `MAX_SCHEDULE_LEN` of them.
+    ///
+    /// Steps [`Self::select_from`] forward from the current state: a pick
+    /// uses up a forced switch and, unless it was that forced switch, the
+    /// front of a replayed schedule, just as in [`Self::find_next_task`].
+    /// Every switch is taken as a yield, so the picks run on a copy of the
+    /// statuses where the `Running` caller is `Ready`, and so is each task
+    /// the forecast switches away from again. The caller can thus show up in
+    /// the forecast. Stops early when nothing is runnable.
+    fn predict_schedule(&self, n: usize) -> Vec<usize> {
+        let inner = self.inner.exclusive_access();
+        let mut current = inner.current_task;
+        let mut statuses = inner.statuses();
+        if statuses[current] == TaskStatus::Running {
+            statuses[current] = TaskStatus::Ready;
+        }
+        let mut forced_next = inner.forced_next;
+        let mut replay = inner.replay.iter().copied().peekable();
+        let n = n.min(MAX_SCHEDULE_LEN);
+        let mut schedule = Vec::with_capacity(n);
+        while schedule.len() < n {
+            let pick = self.select_from(
+                &inner,
+                &statuses,
+                current,
+                forced_next,
+                replay.peek().copied(),
+            );
+            let (id, selected_by) = match pick {
+                Some(pick) => pick,
+                None => break,
+            };
+            if !matches!(selected_by, SelectReason::Directed) {
+                replay.next();
+            }
+            forced_next = None;
+            current = id;
+            schedule.push(id);
+        }
+        schedule
+    }
+
+    /// The runnable task furthest below its `min_share_permille`, if any.
+    ///
+    /// A task's actual share is [`TaskManagerInner::cpu_share_permille`].
+    /// Nobody is starved before any user time was charged, and the fallback
+    /// task is never boosted. Runnable is judged by `statuses`.
+    fn most_starved(
+        &self,
+        inner: &TaskManagerInner,
+        statuses: &[TaskStatus; MAX_APP_NUM],
+    ) -> Option<usize> {
+        let tasks = &inner.tasks[..self.num_app];
+        if tasks.iter().all(|t| t.user_time == 0) {
+            return None;
+        }
+        tasks
+            .iter()
+            .enumerate()
+            .filter(|(id, _)| Some(*id) != inner.fallback_task && statuses[*id].is_runnable())
+            .map(|(id, t)| {
+                let share = inner.cpu_share_permille(id) as usize;
+                (id, t.min_share_permille.saturating_sub(share))
+            })
+            .filter(|(_, deficit)| *deficit > 0)
+            .max_by_key(|(_, deficit)| *deficit)
+            .map(|(id, _)| id)
+    }
+
+    /// The runnable task with the earliest `deadline_at`, if any has one.
+    ///
+    /// Tasks without a deadline are less urgent than any task with one, so
+    /// they are left to round robin. The fallback task is never picked here.
+    /// Runnable is judged by `statuses`.
+    fn earliest_deadline(
+        &self,
+        inner: &TaskManagerInner,
+        statuses: &[TaskStatus; MAX_APP_NUM],
+    ) -> Option<usize> {
+        inner.tasks[..self.num_app]
+            .iter()
+            .enumerate()
+            .filter(|(id, t)| {
+                Some(*id) != inner.fallback_task
+                    && statuses[*id].is_runnable()
+                    && t.deadline_at != 0
+            })
+            .min_by_key(|(_, t)| t.deadline_at)
+            .map(|(id, _)| id)
+    }
+
+    /// Set the current task's deadline `ms` milliseconds from now, 0 to clear it.
+    fn set_current_deadline(&self, ms: usize) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        let task = &mut inner.tasks[current];
+        task.deadline_at = match ms {
+            0 => 0,
+            ms => get_time_us().saturating_add(ms.saturating_mul(1000)),
+        };
+        task.deadline_missed = false;
+    }
+
+    /// Guarantee task `id` at least `permille` of the CPU.
+    ///
+    /// Returns `false` if `id` is out of range or the guarantees of all tasks
+    /// would add up to more than 1000 permille.
+    fn set_min_share(&self, id: usize, permille: usize) -> bool {
+        if id >= self.num_app {
+            return false;
+        }
+        let mut inner = self.inner.exclusive_access();
+        let others: usize = inner.tasks[..self.num_app]
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != id)
+            .map(|(_, t)| t.min_share_permille)
+            .sum();
+        if permille > 1000 - others {
+            return false;
+        }
+        inner.tasks[id].min_share_permille = permille;
+        true
+    }
+
+    /// Number of apps dispatched at least once, and the number of apps loaded.
+    ///
+    /// A task counts as dispatched from its first [`TaskControlBlock::dispatch`],
+    /// which is also where its `init_time` is stamped.
+    fn get_dispatch_coverage(&self) -> (usize, usize) {
+        let inner = self.inner.exclusive_access();
+        let dispatched = inner.tasks[..self.num_app]
+            .iter()
+            .filter(|t| t.dispatch_count != 0)
+            .count();
+        (dispatched, self.num_app)
+    }
+
+    /// How evenly dispatches are spread over the loaded tasks, in permille.
+    ///
+    /// The fewest dispatches of any task over the most, so 1000 means every
+    /// task was dispatched equally often, also before any dispatch, and 0
+    /// that some task never ran while another did.
+    fn dispatch_balance_permille(&self) -> usize {
+        let inner = self.inner.exclusive_access();
+        let counts = inner.tasks[..self.num_app].iter().map(|t| t.dispatch_count);
+        let min = counts.clone().min().unwrap_or(0);
+        let max = counts.max().unwrap_or(0);
+        if max == 0 {
+            return 1000;
+        }
+        min * 1000 / max
+    }
+
+    /// Calls of `find_next_task` made to switch tasks and the microseconds they took in total.
+    ///
+    /// Only the selection is timed, waking sleepers and the switch itself
+    /// are not, unlike [`Self::get_sched_overhead_permille`].
+    fn get_selection_stats(&self) -> (usize, usize) {
+        let inner = self.inner.exclusive_access();
+        (inner.selection_calls, inner.selection_time_us)
+    }
+
+    /// Time spent scheduling in permille of the time since boot.
+    ///
+    /// Scheduling time is every switch latency added up, the same span
+    /// [`Self::get_recent_switch_latency`] reports on.
+    fn get_sched_overhead_permille(&self) -> usize {
+        let elapsed = get_time_us();
+        if elapsed == 0 {
+            return 0;
+        }
+        self.inner.exclusive_access().sched_time_us * 1000 / elapsed
+    }
+
+    /// Microseconds the kernel spent idle waiting for a sleeper to become due.
+    fn get_idle_time_us(&self) -> usize {
+        self.inner.exclusive_access().idle_time_us
+    }
+
+    /// The longest single stretch in microseconds the kernel spent idle waiting for a sleeper.
+    fn get_max_idle_stretch_us(&self) -> usize {
+        self.inner.exclusive_access().max_idle_stretch_us
+    }
+
+    /// Whether no task is `Ready` or `Running`, so nothing runs until a sleeper wakes.
+    ///
+    /// Asked from a syscall this is always `false`, the caller itself is running.
+    fn is_system_idle(&self) -> bool {
+        let inner = self.inner.exclusive_access();
+        !inner
+            .tasks
+            .iter()
+            .take(self.num_app)
+            .any(|t| matches!(t.task_status, TaskStatus::Ready | TaskStatus::Running))
+    }
+
+    /// Time in microseconds the first sleeper is due to wake, `None` if no task sleeps.
+    ///
+    /// An idle loop can program a one-shot timer for this instead of ticking.
+    fn next_wake_time_us(&self) -> Option<usize> {
+        self.inner.exclusive_access().next_wake_time_us()
+    }
+
+    /// Number of dispatches that picked a task while a runnable one had a higher priority.
+    ///
+    /// A larger `priority` means a higher priority, as for stride scheduling.
+    /// The round-robin policy ignores priorities, so expect this to grow
+    /// whenever priorities differ.
+    fn get_priority_inversions(&self) -> usize {
+        self.inner.exclusive_access().priority_inversions
+    }
+
+    /// Call `hook` once every task has exited, `None` to only stop as before.
+    ///
+    /// The hook runs with `inner` released. If it returns, the kernel
+    /// stops through `finish` under the current [`PanicPolicy`].
+    ///
+    /// [`PanicPolicy`]: crate::lang_items::PanicPolicy
+    fn set_on_all_complete(&self, hook: Option<fn()>) {
+        self.inner.exclusive_access().on_all_complete = hook;
+    }
+
+    /// Stop the kernel once more than `n` context switches happened since boot, 0 for no limit.
+    fn set_max_switches(&self, n: usize) {
+        self.inner.exclusive_access().max_switches = n;
+    }
+
+    /// Copy out the switch trace.
+    fn dump_switch_trace(&self) -> SwitchTraceDump {
+        let inner = self.inner.exclusive_access();
+        let mut dump = SwitchTraceDump {
+            dropped: inner.switch_trace_dropped,
+            len: inner.switch_trace.len(),
+            records: [SwitchRecord {
+                time_us: 0,
+                from_id: 0,
+                to_id: 0,
+                reason: SwitchReason::FirstRun,
+                selected_by: SelectReason::Initial,
+            }; SWITCH_TRACE_LEN],
+        };
+        for (dst, src) in dump.records.iter_mut().zip(inner.switch_trace.iter()) {
+            *dst = *src;
+        }
+        dump
+    }
+
+    /// Start recording the id of every dispatched task, dropping any earlier recording.
+    ///
+    /// Recording stops by itself after `MAX_SCHEDULE_LEN` dispatches.
+    fn record_schedule(&self) {
+        self.inner.exclusive_access().recording = Some(Vec::new());
+    }
+
+    /// Stop recording and return the dispatched ids in order, empty if not recording.
+    fn stop_recording(&self) -> Vec<usize> {
+        self.inner
+            .exclusive_access()
+            .recording
+            .take()
+            .unwrap_or_default()
+    }
+
+    /// Make the coming dispatches follow `schedule`, then the normal policy.
+    ///
+    /// Returns `false` if `schedule` is longer than `MAX_SCHEDULE_LEN` or
+    /// names a task out of range.
+    fn replay_schedule(&self, schedule: Vec<usize>) -> bool {
+        if schedule.len() > MAX_SCHEDULE_LEN || schedule.iter().any(|&id| id >= self.num_app) {
+            return false;
+        }
+        self.inner.exclusive_access().replay = schedule.into();
+        true
+    }
+
+    /// Id of the current task and of the task a switch right now would pick.
+    ///
+    /// The second id is `None` when no other task is runnable.
+    fn peek_next_task(&self) -> (usize, Option<usize>) {
+        let inner = self.inner.exclusive_access();
+        (
+            inner.current_task,
+            self.select_next(&inner).map(|(id, _)| id),
+        )
+    }
+
+    /// Min, max and mean of the last `SWITCH_LATENCY_WINDOW` switch latencies.
+    ///
+    /// Returns `None` before the first switch.
+    fn get_recent_switch_latency(&self) -> Option<SwitchLatency> {
+        let inner = self.inner.exclusive_access();
+        let samples = inner.recent_switches.len();
+        if samples == 0 {
+            return None;
+        }
+        Some(SwitchLatency {
+            samples,
+            min_us: *inner.recent_switches.iter().min().unwrap(),
+            max_us: *inner.recent_switches.iter().max().unwrap(),
+            mean_us: inner.recent_switches.iter().sum::<usize>() / samples,
+        })
+    }
+
+    /// Suspend the current task and make `id` the next task to run.
+    ///
+    /// Returns `false`, leaving the current task running, if `id` is out of
+    /// range or not `Ready`.
+    fn request_switch_to(&self, id: usize) -> bool {
+        let mut inner = self.inner.exclusive_access();
+        if id >= self.num_app || !inner.tasks[id].task_status.is_runnable() {
+            return false;
+        }
+        let current = inner.current_task;
+        inner.tasks[current].set_status(TaskStatus::Ready, get_time_us());
+        inner.forced_next = Some(id);
+        true
+    }
+
+    /// Make task `id` the fallback picked only when nothing else is `Ready`.
+    ///
+    /// Returns `false` if `id` is out of range.
+    fn set_fallback_task(&self, id: usize) -> bool {
+        if id >= self.num_app {
+            return false;
+        }
+        self.inner.exclusive_access().fallback_task = Some(id);
+        true
+    }
+
+    /// Switch current `Running` task to the task we have found,
+    /// or there is no `Ready` task and we can exit with all applications completed
+    ///
+    /// The switch latency recorded here is measured from the scheduling pass
+    /// that found `next` to right before `__switch`, so time spent idle while
+    /// everybody sleeps is not counted. Picking the current task again, say
+    /// on a lone task's yield, is not a switch and records nothing.
+    fn run_next_task(&self, reason: SwitchReason) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        // whatever happens next, the current task's stretch on the CPU ends here
+        let voluntary = !matches!(reason, SwitchReason::Preempt | SwitchReason::Freeze);
+        inner.tasks[current].end_run(get_time_us(), voluntary);
+        drop(inner);
+        let (next, selected_by, start) = loop {
+            let start = get_time_us();
+            self.wake_sleepers();
+            let pick_start = get_time_us();
+            let outcome = self.find_next_task();
+            let mut inner = self.inner.exclusive_access();
+            inner.selection_calls += 1;
+            inner.selection_time_us += time_delta(get_time_us(), pick_start);
+            drop(inner);
+            match outcome {
+                NextTask::Run(next, selected_by) => break (next, selected_by, start),
+                NextTask::AllExited => {
+                    let on_all_complete = self.inner.exclusive_access().on_all_complete;
+                    if let Some(hook) = on_all_complete {
+                        hook();
+                    }
+                    finish("All applications completed!")
+                }
+                NextTask::AllBlocked {
+                    earliest_wake: Some(_),
+                } => {
+                    // nothing to run yet, wait for timer ticks until the earliest sleeper is due
+                    let idle_start = get_time_us();
+                    idle_wait();
+                    let stretch = time_delta(get_time_us(), idle_start);
+                    let mut inner = self.inner.exclusive_access();
+                    inner.idle_time_us += stretch;
+                    inner.max_idle_stretch_us = inner.max_idle_stretch_us.max(stretch);
+                }
+                NextTask::AllBlocked {
+                    earliest_wake: None,
+                } => {
+                    self.inner.exclusive_access().print_task_table();
+                    fail("No task is ready or sleeping, the blocked tasks wait forever!")
+                }
+            }
+        };
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        kassert!(
+            inner,
+            inner.tasks[next].task_status.is_runnable(),
+            "picked task {} which is {:?}",
+            next,
+            inner.tasks[next].task_status
+        );
+        if next == current {
+            // the caller is the only runnable task, keep it on the CPU without a `__switch`
+            let now = get_time_us();
+            let quantum_us = inner.quantum_us();
+            let task = &mut inner.tasks[current];
+            task.adopt_quantum(quantum_us);
+            task.resume(now);
+            inner.check_invariants();
+            return;
+        }
+        // the current task gives up the CPU inside the syscall it trapped with
+        if let (task, TrapCause::Syscall, syscall_id) = inner.kernel_entry {
+            let tcb = &mut inner.tasks[current];
+            // keyed like `syscall_times`, ids past `u16::MAX` were bucketed as unknown
+            if task == current && syscall_id <= u16::MAX as usize && tcb.counting_enabled {
+                *tcb.syscall_switches.entry(syscall_id as u16).or_insert(0) += 1;
+            }
+        }
+        inner.switch_count += 1;
+        if inner.max_switches != 0 && inner.switch_count > inner.max_switches {
+            let msg = format!("Context switch cap of {} reached!", inner.max_switches);
+            drop(inner);
+            finish(&msg);
+        }
+        let priority = inner.tasks[next].priority;
+        if inner
+            .tasks
+            .iter()
+            .take(self.num_app)
+            .any(|t| t.task_status.is_runnable() && t.priority > priority)
+        {
+            inner.priority_inversions += 1;
+        }
+        let quantum_us = inner.quantum_us();
+        inner.tasks[next].adopt_quantum(quantum_us);
+        inner.tasks[next].dispatch(get_time_us());
+        inner.current_task = next;
+        inner.check_invariants();
+        if inner.recent_switches.len() == SWITCH_LATENCY_WINDOW {
+            inner.recent_switches.pop_front();
+        }
+        let latency = time_delta(get_time_us(), start);
+        inner.recent_switches.push_back(latency);
+        inner.sched_time_us += latency;
+        if let Some(schedule) = inner.recording.as_mut() {
+            if schedule.len() < MAX_SCHEDULE_LEN {
+                schedule.push(next);
+            }
+        }
+        inner.trace_switch(current, next, reason, selected_by);
+        let current_task_cx_ptr = &mut inner.tasks[current].task_cx as *mut TaskContext;
+        let next_task_cx_ptr = &inner.tasks[next].task_cx as *const TaskContext;
+        drop(inner);
+        // before this, we should drop local variables that must be dropped manually
+        unsafe {
+            __switch(current_task_cx_ptr, next_task_cx_ptr);
+        }
+        // go back to user mode
+    }
+
+    // LAB1: Try to implement your function to update or get task info!
+    /// Count a syscall of the current task, returning whether to dispatch it.
+    ///
+    /// With syscall id validation on, an id missing from the syscall table
+    /// only bumps the task's unknown-syscall bucket and `false` is returned.
+    /// With `count_failures` off, counting waits for [`Self::record_syscall`].
+    /// A task that turned counting off only gets its syscalls validated.
+    fn increase_syscall_count(&self, syscall_id: usize) -> bool {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        inner.kernel_entry.2 = syscall_id;
+        if inner.validate_syscall_ids && !is_known_syscall(syscall_id) {
+            if inner.tasks[current].counting_enabled {
+                inner.tasks[current].unknown_syscalls += 1;
+            }
+            return false;
+        }
+        if inner.count_failures {
+            inner.count_syscall(syscall_id);
+        }
+        true
+    }
+
+    /// Count syscall `syscall_id` of the current task once it returned `result`.
+    ///
+    /// Only does anything with `count_failures` off, then a non-negative
+    /// `result` is counted. A syscall that does not return, like `sys_exit`,
+    /// and the `sys_task_info` call reading the counts are thus not counted.
+    fn record_syscall(&self, syscall_id: usize, result: isize) {
+        let mut inner = self.inner.exclusive_access();
+        if !inner.count_failures && result >= 0 {
+            inner.count_syscall(syscall_id);
+        }
+    }
+
+    /// Turn syscall counting of the current task on (the default) or off.
+    ///
+    /// While off, every syscall counter of the task stays where it is.
+    fn set_current_counting(&self, enabled: bool) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        inner.tasks[current].counting_enabled = enabled;
+    }
+
+    /// Add `len` bytes successfully written by `sys_write` to the current task.
+    fn add_current_bytes_written(&self, len: usize) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        let task = &mut inner.tasks[current];
+        if task.counting_enabled {
+            task.bytes_written += len;
+        }
+    }
+
+    /// Count syscalls when issued (the default), or only successful ones once they return.
+    fn set_count_failures(&self, enabled: bool) {
+        self.inner.exclusive_access().count_failures = enabled;
+    }
+
+    /// Warn once a task issues more than `per_sec` syscalls per second, 0 to never warn.
+    fn set_syscall_storm_threshold(&self, per_sec: usize) {
+        self.inner.exclusive_access().syscall_storm_per_sec = per_sec;
+    }
+
+    /// Where the current task's time went, see [`TaskControlBlock::time_breakdown`].
+    fn get_time_breakdown(&self) -> TimeBreakdown {
+        let inner = self.inner.exclusive_access();
+        inner.tasks[inner.current_task].time_breakdown(get_time_us())
+    }
+
+    /// Build the [`TaskInfo`] of task `id`, `None` if it is out of range.
+    ///
+    /// Unlike [`Self::get_current_task_info`] this charges nobody for the call.
+    fn get_task_info_by_id(&self, id: usize) -> Option<TaskInfo> {
+        if id >= self.num_app {
+            return None;
+        }
+        let inner = self.inner.exclusive_access();
+        Some(TaskInfo::from_tcb(&inner.tasks[id], get_time_us()))
+    }
+
+    /// Build the [`TaskStats`] of task `id`, `None` if it is out of range.
+    fn get_task_stats(&self, id: usize) -> Option<TaskStats> {
+        if id >= self.num_app {
+            return None;
+        }
+        let inner = self.inner.exclusive_access();
+        let share = inner.cpu_share_permille(id);
+        Some(TaskStats::from_tcb(&inner.tasks[id], get_time_us(), share))
+    }
+
+    /// Turn syscall id validation in [`Self::increase_syscall_count`] on or off.
+    fn set_syscall_validation(&self, enabled: bool) {
+        self.inner.exclusive_access().validate_syscall_ids = enabled;
+    }
+
+    /// Turn syscall tracing of task `id` on or off.
+    ///
+    /// Enabling starts a fresh trace, disabling keeps the recorded entries
+    /// readable. Returns `false` if `id` is out of range.
+    fn set_trace_enabled(&self, id: usize, enabled: bool) -> bool {
+        if id >= self.num_app {
+            return false;
+        }
+        let mut inner = self.inner.exclusive_access();
+        let task = &mut inner.tasks[id];
+        if enabled && !task.trace_enabled {
+            task.trace.clear();
+            task.trace_dropped = 0;
+        }
+        task.trace_enabled = enabled;
+        true
+    }
+
+    /// Syscall trace of task `id`, `None` if `id` is out of range.
+    fn dump_trace(&self, id: usize) -> Option<TraceDump> {
+        if id >= self.num_app {
+            return None;
+        }
+        let inner = self.inner.exclusive_access();
+        Some(inner.tasks[id].trace_dump())
+    }
+
+    /// Build the [`TaskInfo`] of the current task.
+    ///
+    /// With `account_self_overhead` on, the time spent in here is charged to
+    /// the task's `info_overhead_us`, which [`TaskStats`] reports.
+    fn get_current_task_info(&self) -> TaskInfo {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        let start = get_time_us();
+        kassert!(
+            inner,
+            inner.tasks[current].syscall_accounting_ok(),
+            "syscall accounting: counts of task {} don't add up to its total",
+            current
+        );
+        let info = TaskInfo::from_tcb(&inner.tasks[current], start);
+        if inner.account_self_overhead {
+            inner.tasks[current].info_overhead_us += time_delta(get_time_us(), start);
+        }
+        info
+    }
+
+    /// Turn charging [`Self::get_current_task_info`] to the caller on or off.
+    fn set_account_self_overhead(&self, enabled: bool) {
+        self.inner.exclusive_access().account_self_overhead = enabled;
+    }
+
+    /// Time in microseconds it took to build the task manager.
+    fn get_init_duration(&self) -> usize {
+        self.init_duration_us
+    }
+
+    /// Id of the current `Running` task.
+    fn current_task_id(&self) -> usize {
+        self.inner.exclusive_access().current_task
+    }
+
+    /// Id and status of the current task, without building a [`TaskInfo`].
+    fn get_current_task_brief(&self) -> (usize, TaskStatus) {
+        let inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        (current, inner.tasks[current].task_status)
+    }
+
+    /// Set the display name of the current task.
+    fn set_current_task_name(&self, name: &[u8]) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        inner.tasks[current].set_name(name);
+    }
+
+    /// Add `us` microseconds to the system-wide time of `syscall_id`.
+    fn add_system_syscall_time(&self, syscall_id: usize, us: usize) {
+        let mut inner = self.inner.exclusive_access();
+        inner.system_syscall_times[syscall_id] += us as u64;
+    }
+
+    /// Copy out the system-wide time spent in each syscall.
+    fn get_system_syscall_times(&self) -> [u64; MAX_SYSCALL_NUM] {
+        self.inner.exclusive_access().system_syscall_times
+    }
+
+    /// Syscalls issued by all tasks since boot.
+    ///
+    /// A running total, so resetting a task's stats or restarting it does
+    /// not take anything off.
+    fn total_system_syscalls(&self) -> u64 {
+        self.inner.exclusive_access().total_system_syscalls
+    }
+
+    /// Clear the current task's syscall counts and stamp `stats_reset_at`.
+    fn reset_syscall_stats(&self) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        inner.tasks[current].reset_syscall_stats(get_time_us());
+    }
+
+    /// Copy out the current task's per-syscall counts.
+    fn snapshot_syscalls(&self) -> [u32; MAX_SYSCALL_NUM] {
+        let inner = self.inner.exclusive_access();
+        inner.tasks[inner.current_task].syscall_snapshot()
+    }
+
+    /// Record that the current task is about to return to user mode.
+    ///
+    /// Warns if the kernel held the CPU for longer than
+    /// `kernel_residency_warn_us` since the latest trap. After a switch that
+    /// trap was taken by another task, and time spent idle counts as well.
+    fn mark_enter_user(&self) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        let now = get_time_us();
+        let resident = time_delta(now, inner.kernel_entered_at);
+        if resident > inner.kernel_residency_warn_us {
+            match inner.kernel_entry {
+                (task, TrapCause::Syscall, syscall_id) => warn!(
+                    "[kernel] {} us in the kernel for syscall {} of task {}",
+                    resident, syscall_id, task
+                ),
+                (task, cause, _) => warn!(
+                    "[kernel] {} us in the kernel for a {:?} trap of task {}",
+                    resident, cause, task
+                ),
+            }
+        }
+        let task = &mut inner.tasks[current];
+        if task.in_user {
+            warn!("[kernel] task {} entered user mode twice in a row", current);
+        }
+        task.in_user = true;
+        task.user_entered_at = now;
+    }
+
+    /// Warn of kernel paths holding the CPU for more than `us` microseconds, 0 to never warn.
+    fn set_kernel_residency_warn(&self, us: usize) {
+        let threshold = if us == 0 { usize::MAX } else { us };
+        self.inner.exclusive_access().kernel_residency_warn_us = threshold;
+    }
+
+    /// Record that the current task trapped into the kernel and charge it
+    /// the time spent in user mode since the matching [`Self::mark_enter_user`].
+    fn mark_exit_user(&self) {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        let now = get_time_us();
+        inner.kernel_entered_at = now;
+        let task = &mut inner.tasks[current];
+        if !task.in_user {
+            warn!(
+                "[kernel] task {} left user mode without entering it, user time not charged",
+                current
+            );
+            return;
+        }
+        task.user_time += time_delta(now, task.user_entered_at);
+        task.in_user = false;
+    }
+
+    /// Time in microseconds the current task has held the CPU since it was
+    /// last dispatched or picked again.
+    fn get_current_quantum_time_us(&self) -> usize {
+        let inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        time_delta(get_time_us(), inner.tasks[current].run_started_at)
+    }
+
+    /// Dump the stats of every loaded task as CSV.
+    ///
+    /// The header is always `id,status,time_ms,dispatches,yields,preempts`,
+    /// followed by one row per task in id order. `status` is the `TaskStatus`
+    /// variant name and `time_ms` is the time since first dispatch, or 0 for a
+    /// task that never ran. New columns are only ever appended.
+    fn export_tasks_csv(&self) -> String {
+        let inner = self.inner.exclusive_access();
+        let now = get_time_us();
+        let mut csv = String::from("id,status,time_ms,dispatches,yields,preempts\n");
+        for (id, task) in inner.tasks.iter().enumerate().take(self.num_app) {
+            let time_ms = if task.dispatch_count == 0 {
+                0
+            } else {
+                time_delta(now, task.init_time) / 1000
+            };
+            writeln!(
+                csv,
+                "{},{:?},{},{},{},{}",
+                id,
+                task.task_status,
+                time_ms,
+                task.dispatch_count,
+                task.yield_count,
+                task.preempt_count
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    /// Write the stats of task `id` into `buf` as a binary record.
+    ///
+    /// See [`TASK_RECORD_LEN`] for the layout. Returns the bytes written,
+    /// always `TASK_RECORD_LEN`, or 0 if `id` is not loaded or `buf` is too
+    /// short for a whole record.
+    fn encode_task_record(&self, id: usize, buf: &mut [u8]) -> usize {
+        if id >= self.num_app || buf.len() < TASK_RECORD_LEN {
+            return 0;
+        }
+        let inner = self.inner.exclusive_access();
+        let task = &inner.tasks[id];
+        let time_ms = if task.dispatch_count == 0 {
+            0
+        } else {
+            time_delta(get_time_us(), task.init_time) / 1000
+        };
+        buf[0] = TASK_RECORD_VERSION;
+        buf[1] = task.task_status as u8;
+        buf[2..4].copy_from_slice(&(id as u16).to_le_bytes());
+        buf[4..8].copy_from_slice(&(task.priority as u32).to_le_bytes());
+        let fields = [
+            time_ms as u64,
+            task.user_time as u64,
+            task.info_overhead_us as u64,
+            task.dispatch_count as u64,
+            task.total_syscalls,
+            task.yield_count as u64,
+            task.preempt_count as u64,
+        ];
+        for (dst, field) in buf[8..TASK_RECORD_LEN].chunks_exact_mut(8).zip(fields) {
+            dst.copy_from_slice(&field.to_le_bytes());
+        }
+        TASK_RECORD_LEN
+    }
+
+    /// Standard deviation in milliseconds of the user time of all non-`UnInit` tasks.
+    ///
+    /// There is no float here, so everything is integer math on microseconds:
+    /// the mean is rounded down, the squared deviations are summed in `u128`
+    /// so they cannot overflow, and the root of their average is taken with
+    /// [`isqrt`]. Only the final result is truncated to milliseconds. Fewer
+    /// than two tasks have no spread and give 0.
+    fn run_time_stddev_ms(&self) -> usize {
+        let inner = self.inner.exclusive_access();
+        let times: Vec<u128> = inner
+            .tasks
+            .iter()
+            .take(self.num_app)
+            .filter(|t| t.task_status != TaskStatus::UnInit)
+            .map(|t| t.user_time as u128)
+            .collect();
+        let n = times.len() as u128;
+        if n < 2 {
+            return 0;
+        }
+        let mean = times.iter().sum::<u128>() / n;
+        let sum_sq: u128 = times
+            .iter()
+            .map(|&t| {
+                let d = t.abs_diff(mean);
+                d * d
+            })
+            .sum();
+        (isqrt(sum_sq / n) / 1000) as usize
+    }
+
+    /// Gini coefficient in permille of the user time of all non-`UnInit` tasks.
+    ///
+    /// 0 means every task got the same CPU time, values towards 1000 that a
+    /// few tasks got most of it. With the times sorted ascending as `x_1..=x_n`
+    /// this is `2 * sum(i * x_i) / (n * sum(x)) - (n + 1) / n`, all in `u128`
+    /// integer math over a common denominator. Fewer than two tasks, or no
+    /// user time at all, give 0.
+    fn cpu_gini_permille(&self) -> usize {
+        let inner = self.inner.exclusive_access();
+        let mut times: Vec<u128> = inner
+            .tasks
+            .iter()
+            .take(self.num_app)
+            .filter(|t| t.task_status != TaskStatus::UnInit)
+            .map(|t| t.user_time as u128)
+            .collect();
+        drop(inner);
+        let n = times.len() as u128;
+        let sum: u128 = times.iter().sum();
+        if n < 2 || sum == 0 {
+            return 0;
+        }
+        times.sort_unstable();
+        // never below `(n + 1) * sum / 2` for sorted times, so the subtraction cannot wrap
+        let weighted: u128 = times
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| (i as u128 + 1) * x)
+            .sum();
+        ((2000 * weighted - 1000 * (n + 1) * sum) / (n * sum)) as usize
+    }
+
+    /// Fill `buf` with one [`TaskDumpEntry`] per loaded task, in id order.
+    ///
+    /// Returns the number of entries written, at most `buf.len()`.
+    fn dump_task_table(&self, buf: &mut [TaskDumpEntry]) -> usize {
+        let inner = self.inner.exclusive_access();
+        let now = get_time_us();
+        let tasks = inner.tasks.iter().take(self.num_app).enumerate();
+        let mut n = 0;
+        for (entry, (id, task)) in buf.iter_mut().zip(tasks) {
+            *entry = TaskDumpEntry {
+                id,
+                status: task.task_status as usize,
+                priority: task.priority,
+                time_ms: if task.dispatch_count == 0 {
+                    0
+                } else {
+                    time_delta(now, task.init_time) / 1000
+                },
+                user_time_us: task.user_time,
+                dispatches: task.dispatch_count,
+            };
+            n += 1;
+        }
+        n
+    }
+
+    /// The `Ready` or `Running` task that has been off the CPU the longest.
+    ///
+    /// A `Ready` task last ran when its previous stretch ended, the `Running`
+    /// one is running now. Tasks that never ran do not count, so this is
+    /// `None` until some task has.
+    fn get_lru_task(&self) -> Option<usize> {
+        let inner = self.inner.exclusive_access();
+        let now = get_time_us();
+        inner.tasks[..self.num_app]
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.dispatch_count != 0)
+            .filter_map(|(id, t)| match t.task_status {
+                TaskStatus::Ready => Some((id, t.last_ran_at)),
+                TaskStatus::Running => Some((id, now)),
+                _ => None,
+            })
+            .min_by_key(|(_, last_ran_at)| *last_ran_at)
+            .map(|(id, _)| id)
+    }
+
+    /// The loaded task with the most syscalls counted, the lowest id on a tie.
+    ///
+    /// `None` until some task made a syscall.
+    fn busiest_task_by_syscalls(&self) -> Option<usize> {
+        let inner = self.inner.exclusive_access();
+        inner.tasks[..self.num_app]
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.total_syscalls != 0)
+            .max_by(|(a, ta), (b, tb)| ta.total_syscalls.cmp(&tb.total_syscalls).then(b.cmp(a)))
+            .map(|(id, _)| id)
+    }
+
+    /// FNV-1a hash of every loaded task's status and dispatch count, in id order.
+    ///
+    /// Only the scheduling sequence feeds into it, no timestamps, so the
+    /// same run of decisions always gives the same fingerprint.
+    fn state_fingerprint(&self) -> u64 {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0100_0000_01b3;
+        let inner = self.inner.exclusive_access();
+        let mut hash = FNV_OFFSET;
+        for t in inner.tasks[..self.num_app].iter() {
+            for value in [t.task_status as u64, t.dispatch_count as u64] {
+                for byte in value.to_le_bytes() {
+                    hash ^= byte as u64;
+                    hash = hash.wrapping_mul(FNV_PRIME);
+                }
+            }
+        }
+        hash
+    }
+
+    /// Ids of all loaded tasks in `status`, in id order.
+    fn list_tasks_by_status(&self, status: TaskStatus) -> Vec<usize> {
+        let inner = self.inner.exclusive_access();
+        inner.tasks[..self.num_app]
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.task_status == status)
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Slots below `num_app` still `UnInit`, in id order.
+    ///
+    /// Every slot of a loaded app turns `Ready` while `TASK_MANAGER` is
+    /// built, so anything here points at a loader and initializer mismatch.
+    /// `num_app` is capped at the number of slots.
+    fn list_uninit_slots_below(&self, num_app: usize) -> Vec<usize> {
+        let inner = self.inner.exclusive_access();
+        let n = num_app.min(inner.tasks.len());
+        inner.tasks[..n]
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.task_status == TaskStatus::UnInit)
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// The status of every slot, `UnInit` ones included, read in a single borrow
+    /// so the statuses are consistent with each other.
+    fn snapshot_statuses(&self) -> [TaskStatus; MAX_APP_NUM] {
+        self.inner.exclusive_access().statuses()
+    }
+
+    /// Set the priority of the current task, clamped by [`clamp_priority`].
+    ///
+    /// Returns the priority actually set.
+    fn set_current_priority(&self, priority: usize) -> usize {
+        let mut inner = self.inner.exclusive_access();
+        let current = inner.current_task;
+        inner.tasks[current].set_priority(priority);
+        clamp_priority(priority)
+    }
+
+    /// Swap the priorities of tasks `id_a` and `id_b` under a single borrow.
+    ///
+    /// Returns `false` if either id is out of range or the slot is `UnInit`.
+    fn swap_priorities(&self, id_a: usize, id_b: usize) -> bool {
+        let mut inner = self.inner.exclusive_access();
+        if id_a >= self.num_app || id_b >= self.num_app {
+            return false;
+        }
+        if inner.tasks[id_a].task_status == TaskStatus::UnInit
+            || inner.tasks[id_b].task_status == TaskStatus::UnInit
+        {
+            return false;
+        }
+        let priority_a = inner.tasks[id_a].own_priority();
+        let priority_b = inner.tasks[id_b].own_priority();
+        inner.tasks[id_a].set_priority(priority_b);
+        inner.tasks[id_b].set_priority(priority_a);
+        true
+    }
+}
+
+/// Integer square root, rounded down, by Newton's method.
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+/// Wait for the next timer tick with `wfi` instead of spinning.
+///
+/// `__alltraps` only handles traps from U-mode, so `sstatus.SIE` stays clear
+/// and the timer interrupt is never taken here. `wfi` still wakes up once the
+/// timer interrupt enabled in `sie` is pending, and re-arming the timer
+/// clears it again before we return to user mode.
+fn idle_wait() {
+    unsafe {
+        riscv::asm::wfi();
+    }
+    set_next_trigger();
+}
+
+/// Build `TASK_MANAGER` now if nothing has touched it yet, a no-op afterwards.
+///
+/// Its initializer prints and allocates, so boot calls this at a known safe
+/// point instead of leaving it to whichever access comes first.
+pub fn ensure_initialized() {
+    lazy_static::initialize(&TASK_MANAGER);
+}
+
+/// Run the first task in task list.
+pub fn run_first_task() {
+    println!("run_first_task start");
+    TASK_MANAGER.run_first_task();
+}
+
+/// Switch current `Running` task to the task we have found,
+/// or there is no `Ready` task and we can exit with all applications completed
+fn run_next_task(reason: SwitchReason) {
+    TASK_MANAGER.run_next_task(reason);
+}
+
+/// Restart the whole workload from the boot state, see [`TaskManager::soft_reset`].
+pub fn soft_reset() -> ! {
+    TASK_MANAGER.soft_reset()
+}
+
+/// Change the status of current `Running` task into `Ready`.
+fn mark_current_suspended() {
+    TASK_MANAGER.mark_current_suspended();
+}
+
+/// Change the status of current `Running` task into `Exited` for `reason`.
+fn mark_current_exited(reason: ExitReason) {
+    TASK_MANAGER.mark_current_exited(reason);
+}
+
+/// Change the status of current `Running` task into `Exited` for a fault.
+fn mark_current_crashed() {
+    mark_current_exited(ExitReason::Crashed);
+}
+
+/// Run an exited task again, see [`TaskManager::restart_task`].
+pub fn restart_task(id: usize) -> bool {
+    TASK_MANAGER.restart_task(id)
+}
+
+/// Tasks related to `id` by restarts, see [`TaskManager::list_related`].
+pub fn list_related(id: usize) -> Option<Vec<usize>> {
+    TASK_MANAGER.list_related(id)
+}
+
+/// Time a slot sat exited before its last restart, see [`TaskManager::get_slot_idle_us`].
+pub fn get_slot_idle_us(id: usize) -> Option<usize> {
+    TASK_MANAGER.get_slot_idle_us(id)
+}
+
+/// Stats of a task's run before its last restart, see [`TaskManager::get_prev_run_info`].
+pub fn get_prev_run_info(id: usize) -> Option<TaskInfoSummary> {
+    TASK_MANAGER.get_prev_run_info(id)
+}
+
+/// Id and exit time of the task that exited last, see [`TaskManager::get_last_exited`].
+pub fn get_last_exited() -> (usize, usize) {
+    TASK_MANAGER.get_last_exited()
+}
+
+/// Configure a barrier, see [`TaskManager::barrier_init`].
+pub fn barrier_init(id: usize, count: usize) -> bool {
+    TASK_MANAGER.barrier_init(id, count)
+}
+
+/// Wait on barrier `id` until it releases, see [`TaskManager::barrier_arrive`].
+pub fn barrier_wait(id: usize) -> bool {
+    match TASK_MANAGER.barrier_arrive(id) {
+        None => false,
+        Some(true) => {
+            run_next_task(SwitchReason::Block);
+            true
+        }
+        Some(false) => true,
+    }
+}
+
+/// Wait for task `id` to exit, giving up after `timeout_ms`, 0 for never.
+///
+/// Returns why `id` exited or froze, `ExitReason::None` if the wait timed
+/// out, and `None` if there is no such task to wait for, see
+/// [`TaskManager::begin_wait_task`].
+pub fn wait_task(id: usize, timeout_ms: usize) -> Option<ExitReason> {
+    match TASK_MANAGER.begin_wait_task(id, timeout_ms)? {
+        true => {
+            run_next_task(SwitchReason::Block);
+            Some(TASK_MANAGER.end_wait_task())
+        }
+        false => TASK_MANAGER.get_exit_reason(id),
+    }
+}
+
+/// Sleep the current 'Running' task for `us` microseconds and run the next task.
+///
+/// Sleepers are only checked for when the scheduler runs, so the task wakes
+/// on the first timer tick or switch at or after `us`: the resolution is
+/// bounded by the timer interrupt period, never below `us`.
+pub fn sleep_current_and_run_next(us: usize) {
+    TASK_MANAGER.mark_current_sleeping(us);
+    run_next_task(SwitchReason::Block);
+}
+
+/// Block the current task until the absolute time `abs_us` and run the next task.
+///
+/// Unlike [`sleep_current_and_run_next`] the wake-up instant does not depend
+/// on when the call is made, so periodic wake-ups do not drift. Returns
+/// `false` right away if `abs_us` has already passed.
+pub fn sleep_until_and_run_next(abs_us: usize) -> bool {
+    if !TASK_MANAGER.mark_current_sleeping_until(abs_us) {
+        return false;
+    }
+    run_next_task(SwitchReason::Block);
+    true
+}
+
+/// Suspend the current 'Running' task and run the next task in task list.
+pub fn suspend_current_and_run_next(reason: SwitchReason) {
+    mark_current_suspended();
+    run_next_task(reason);
+}
+
+/// Suspend the current 'Running' task and run task `id` next, bypassing the policy.
+///
+/// Returns `false` without switching if `id` is out of range or not `Ready`.
+pub fn switch_to(id: usize) -> bool {
+    if !TASK_MANAGER.request_switch_to(id) {
+        return false;
+    }
+    run_next_task(SwitchReason::Directed);
+    true
+}
+
+/// Suspend the current 'Running' task because it yielded, and run the next task.
+pub fn yield_current_and_run_next() {
+    TASK_MANAGER.count_current_yield();
+    suspend_current_and_run_next(SwitchReason::Yield);
+}
+
+/// Suspend the current 'Running' task because its time is up, and run the next task.
+pub fn preempt_current_and_run_next() {
+    TASK_MANAGER.count_current_preempt();
+    suspend_current_and_run_next(SwitchReason::Preempt);
+}
+
+/// Count a trap of the current task, see [`TrapCause`].
+pub fn count_current_trap(cause: TrapCause) {
+    TASK_MANAGER.count_current_trap(cause);
+}
+
+/// Traps taken by the current task, see [`TaskManager::get_current_kernel_entries`].
+pub fn get_current_kernel_entries() -> usize {
+    TASK_MANAGER.get_current_kernel_entries()
+}
+
+/// Raise the current task to the priority ceiling until the matching [`priority_boost_end`].
+pub fn priority_boost_begin() {
+    TASK_MANAGER.priority_boost_begin();
+}
+
+/// End a critical region opened by [`priority_boost_begin`], `false` if none is open.
+pub fn priority_boost_end() -> bool {
+    TASK_MANAGER.priority_boost_end()
+}
+
+/// Charge the current task's slice on a timer tick, returning `true` if it should be preempted.
+pub fn tick_current_slice() -> bool {
+    TASK_MANAGER.tick_current_slice()
+}
+
+/// Microseconds left in the current task's time slice.
+pub fn get_remaining_slice_us() -> usize {
+    TASK_MANAGER.get_remaining_slice_us()
+}
+
+/// Turn timer preemption on or off, see [`TaskManager::set_preemption_enabled`].
+pub fn set_preemption_enabled(enabled: bool) {
+    TASK_MANAGER.set_preemption_enabled(enabled);
+}
+
+/// Set the default slice length, see [`TaskManager::set_global_quantum_ms`].
+pub fn set_global_quantum_ms(ms: usize) -> bool {
+    TASK_MANAGER.set_global_quantum_ms(ms)
+}
+
+/// The default slice length, see [`TaskManager::get_global_quantum_ms`].
+pub fn get_global_quantum_ms() -> usize {
+    TASK_MANAGER.get_global_quantum_ms()
+}
+
+/// Change a task's time slice, see [`TaskManager::set_time_slice`].
+///
+/// If the current task has already overrun its new slice it is preempted
+/// right away. Returns `false` if the slice was rejected.
+pub fn set_time_slice(id: usize, ms: usize) -> bool {
+    match TASK_MANAGER.set_time_slice(id, ms) {
+        None => false,
+        Some(true) => {
+            preempt_current_and_run_next();
+            true
+        }
+        Some(false) => true,
+    }
+}
+
+/// Exit the current 'Running' task and run the next task in task list.
+pub fn exit_current_and_run_next() {
+    mark_current_exited(ExitReason::Completed);
+    run_next_task(SwitchReason::Exit);
+}
+
+/// Kill the current task for a fault and run the next task.
+pub fn crash_current_and_run_next() {
+    mark_current_crashed();
+    run_next_task(SwitchReason::Exit);
+}
+
+/// Whether the current task has used up its CPU budget, see [`TaskManager::set_cpu_budget`].
+pub fn is_current_over_budget() -> bool {
+    TASK_MANAGER.is_current_over_budget()
+}
+
+/// Reap the current task for exceeding its CPU budget and run the next task.
+pub fn reap_current_and_run_next() {
+    mark_current_exited(ExitReason::BudgetExceeded);
+    run_next_task(SwitchReason::Exit);
+}
+
+/// Set the auto-freeze threshold, see [`TaskManager::set_auto_freeze_threshold`].
+pub fn set_auto_freeze_threshold(ms: usize) {
+    TASK_MANAGER.set_auto_freeze_threshold(ms);
+}
+
+/// Whether the current task ran too long in one go, see [`TaskManager::is_current_runaway`].
+pub fn is_current_runaway() -> bool {
+    TASK_MANAGER.is_current_runaway()
+}
+
+/// Freeze the current runaway task and run the next task.
+pub fn freeze_current_and_run_next() {
+    TASK_MANAGER.mark_current_frozen();
+    run_next_task(SwitchReason::Freeze);
+}
+
+/// Limit a task's user time, see [`TaskManager::set_cpu_budget`].
+pub fn set_cpu_budget(id: usize, us: usize) -> bool {
+    TASK_MANAGER.set_cpu_budget(id, us)
+}
+
+/// Raise a task's CPU budget, see [`TaskManager::extend_budget`].
+pub fn extend_budget(id: usize, ms: usize) -> bool {
+    TASK_MANAGER.extend_budget(id, ms)
+}
+
+// LAB1: Public functions implemented here provide interfaces.
+// You may use TASK_MANAGER member functions to handle requests.
+pub fn increase_syscall_count(syscall_id: usize) -> bool {
+    TASK_MANAGER.increase_syscall_count(syscall_id)
+}
+
+/// Count a syscall that returned `result`, see [`TaskManager::record_syscall`].
+pub fn record_syscall(syscall_id: usize, result: isize) {
+    TASK_MANAGER.record_syscall(syscall_id, result);
+}
+
+/// Turn counting the current task's syscalls on or off, see [`TaskManager::set_current_counting`].
+pub fn set_current_counting(enabled: bool) {
+    TASK_MANAGER.set_current_counting(enabled);
+}
+
+/// Count bytes written by the current task, see [`TaskManager::add_current_bytes_written`].
+pub fn add_current_bytes_written(len: usize) {
+    TASK_MANAGER.add_current_bytes_written(len);
+}
+
+/// Count failed syscalls too or not, see [`TaskManager::set_count_failures`].
+pub fn set_count_failures(enabled: bool) {
+    TASK_MANAGER.set_count_failures(enabled);
+}
+
+/// Set the syscall storm warning threshold, see [`TaskManager::set_syscall_storm_threshold`].
+pub fn set_syscall_storm_threshold(per_sec: usize) {
+    TASK_MANAGER.set_syscall_storm_threshold(per_sec);
+}
+
+/// Turn syscall id validation on or off, see [`TaskManager::increase_syscall_count`].
+pub fn set_syscall_validation(enabled: bool) {
+    TASK_MANAGER.set_syscall_validation(enabled);
+}
 
 pub fn get_current_task_info() -> TaskInfo {
     TASK_MANAGER.get_current_task_info()
-}
\ No newline at end of file
+}
+
+/// Split the current task's time, see [`TaskManager::get_time_breakdown`].
+pub fn get_time_breakdown() -> TimeBreakdown {
+    TASK_MANAGER.get_time_breakdown()
+}
+
+/// Info of any loaded task, see [`TaskManager::get_task_info_by_id`].
+pub fn get_task_info_by_id(id: usize) -> Option<TaskInfo> {
+    TASK_MANAGER.get_task_info_by_id(id)
+}
+
+/// Extended stats of any loaded task, see [`TaskManager::get_task_stats`].
+pub fn get_task_stats(id: usize) -> Option<TaskStats> {
+    TASK_MANAGER.get_task_stats(id)
+}
+
+/// Turn self-overhead accounting on or off, see [`TaskManager::get_current_task_info`].
+pub fn set_account_self_overhead(enabled: bool) {
+    TASK_MANAGER.set_account_self_overhead(enabled);
+}
+
+/// Turn syscall tracing of a task on or off, see [`TaskManager::set_trace_enabled`].
+pub fn set_trace_enabled(id: usize, enabled: bool) -> bool {
+    TASK_MANAGER.set_trace_enabled(id, enabled)
+}
+
+/// Syscall trace of task `id`, `None` if `id` is out of range.
+pub fn dump_trace(id: usize) -> Option<TraceDump> {
+    TASK_MANAGER.dump_trace(id)
+}
+
+/// Time in microseconds it took to build `TASK_MANAGER`.
+pub fn get_init_duration() -> usize {
+    TASK_MANAGER.get_init_duration()
+}
+
+/// Id of the current `Running` task.
+pub fn current_task_id() -> usize {
+    TASK_MANAGER.current_task_id()
+}
+
+/// Id and status of the current task, a cheap subset of [`get_current_task_info`].
+pub fn get_current_task_brief() -> (usize, TaskStatus) {
+    TASK_MANAGER.get_current_task_brief()
+}
+
+/// Set the display name of the current task, truncating long names.
+pub fn set_current_task_name(name: &[u8]) {
+    TASK_MANAGER.set_current_task_name(name);
+}
+
+/// Charge `us` microseconds to `syscall_id` system-wide.
+pub fn add_system_syscall_time(syscall_id: usize, us: usize) {
+    TASK_MANAGER.add_system_syscall_time(syscall_id, us);
+}
+
+/// Total time in microseconds spent in each syscall by all tasks.
+pub fn get_system_syscall_times() -> [u64; MAX_SYSCALL_NUM] {
+    TASK_MANAGER.get_system_syscall_times()
+}
+
+/// Syscalls issued since boot, see [`TaskManager::total_system_syscalls`].
+pub fn total_system_syscalls() -> u64 {
+    TASK_MANAGER.total_system_syscalls()
+}
+
+/// Clear the current task's syscall counts.
+pub fn reset_syscall_stats() {
+    TASK_MANAGER.reset_syscall_stats();
+}
+
+/// Copy out the current task's per-syscall counts.
+pub fn snapshot_syscalls() -> [u32; MAX_SYSCALL_NUM] {
+    TASK_MANAGER.snapshot_syscalls()
+}
+
+/// Mark the current task as returning to user mode.
+pub fn mark_enter_user() {
+    TASK_MANAGER.mark_enter_user();
+}
+
+/// Mark the current task as having trapped into the kernel.
+pub fn mark_exit_user() {
+    TASK_MANAGER.mark_exit_user();
+}
+
+/// Set the kernel residency warning threshold, see [`TaskManager::mark_enter_user`].
+pub fn set_kernel_residency_warn(us: usize) {
+    TASK_MANAGER.set_kernel_residency_warn(us);
+}
+
+/// Time the current task has spent on the CPU in this scheduling quantum.
+pub fn get_current_quantum_time_us() -> usize {
+    TASK_MANAGER.get_current_quantum_time_us()
+}
+
+/// Stats of every loaded task as CSV, see [`TaskManager::export_tasks_csv`].
+pub fn export_tasks_csv() -> String {
+    TASK_MANAGER.export_tasks_csv()
+}
+
+/// Inequality of user time across tasks, see [`TaskManager::cpu_gini_permille`].
+pub fn cpu_gini_permille() -> usize {
+    TASK_MANAGER.cpu_gini_permille()
+}
+
+/// Spread of user time across tasks, see [`TaskManager::run_time_stddev_ms`].
+pub fn run_time_stddev_ms() -> usize {
+    TASK_MANAGER.run_time_stddev_ms()
+}
+
+/// Summary of the latest switch latencies, see [`TaskManager::get_recent_switch_latency`].
+pub fn get_recent_switch_latency() -> Option<SwitchLatency> {
+    TASK_MANAGER.get_recent_switch_latency()
+}
+
+/// Current task id and the id that would run next, see [`TaskManager::peek_next_task`].
+pub fn peek_next_task() -> (usize, Option<usize>) {
+    TASK_MANAGER.peek_next_task()
+}
+
+/// Cost of picking the next task, see [`TaskManager::get_selection_stats`].
+pub fn get_selection_stats() -> (usize, usize) {
+    TASK_MANAGER.get_selection_stats()
+}
+
+/// Apps ever dispatched and apps loaded, see [`TaskManager::get_dispatch_coverage`].
+pub fn get_dispatch_coverage() -> (usize, usize) {
+    TASK_MANAGER.get_dispatch_coverage()
+}
+
+/// Balance of dispatch counts in permille, see [`TaskManager::dispatch_balance_permille`].
+pub fn dispatch_balance_permille() -> usize {
+    TASK_MANAGER.dispatch_balance_permille()
+}
+
+/// Scheduling overhead in permille, see [`TaskManager::get_sched_overhead_permille`].
+pub fn get_sched_overhead_permille() -> usize {
+    TASK_MANAGER.get_sched_overhead_permille()
+}
+
+/// Microseconds the kernel spent idle, waiting for sleeping tasks.
+pub fn get_idle_time_us() -> usize {
+    TASK_MANAGER.get_idle_time_us()
+}
+
+/// Longest single idle stretch, see [`TaskManager::get_max_idle_stretch_us`].
+pub fn get_max_idle_stretch_us() -> usize {
+    TASK_MANAGER.get_max_idle_stretch_us()
+}
+
+/// Whether no task can run right now, see [`TaskManager::is_system_idle`].
+pub fn is_system_idle() -> bool {
+    TASK_MANAGER.is_system_idle()
+}
+
+/// When the first sleeper wakes, see [`TaskManager::next_wake_time_us`].
+pub fn next_wake_time_us() -> Option<usize> {
+    TASK_MANAGER.next_wake_time_us()
+}
+
+/// Dispatches that skipped a higher priority task, see [`TaskManager::get_priority_inversions`].
+pub fn get_priority_inversions() -> usize {
+    TASK_MANAGER.get_priority_inversions()
+}
+
+/// Declare a deadline for the current task, see [`TaskManager::set_current_deadline`].
+pub fn set_current_deadline(ms: usize) {
+    TASK_MANAGER.set_current_deadline(ms);
+}
+
+/// Guarantee a task a minimum CPU share, see [`TaskManager::set_min_share`].
+pub fn set_min_share(id: usize, permille: usize) -> bool {
+    TASK_MANAGER.set_min_share(id, permille)
+}
+
+/// Install the all-completed hook, see [`TaskManager::set_on_all_complete`].
+pub fn set_on_all_complete(hook: Option<fn()>) {
+    TASK_MANAGER.set_on_all_complete(hook);
+}
+
+/// Cap the total number of context switches, see [`TaskManager::set_max_switches`].
+pub fn set_max_switches(n: usize) {
+    TASK_MANAGER.set_max_switches(n);
+}
+
+/// The latest context switches, oldest first.
+pub fn dump_switch_trace() -> SwitchTraceDump {
+    TASK_MANAGER.dump_switch_trace()
+}
+
+/// Start recording the schedule, see [`TaskManager::record_schedule`].
+pub fn record_schedule() {
+    TASK_MANAGER.record_schedule();
+}
+
+/// Stop recording and return the recorded schedule.
+pub fn stop_recording() -> Vec<usize> {
+    TASK_MANAGER.stop_recording()
+}
+
+/// Replay a recorded schedule, see [`TaskManager::replay_schedule`].
+pub fn replay_schedule(schedule: Vec<usize>) -> bool {
+    TASK_MANAGER.replay_schedule(schedule)
+}
+
+/// Make task `id` the scheduler's last resort, see [`TaskManager::set_fallback_task`].
+pub fn set_fallback_task(id: usize) -> bool {
+    TASK_MANAGER.set_fallback_task(id)
+}
+
+/// Encode the stats of task `id`, see [`TaskManager::encode_task_record`].
+pub fn encode_task_record(id: usize, buf: &mut [u8]) -> usize {
+    TASK_MANAGER.encode_task_record(id, buf)
+}
+
+/// Copy the task table into `buf`, see [`TaskManager::dump_task_table`].
+pub fn dump_task_table(buf: &mut [TaskDumpEntry]) -> usize {
+    TASK_MANAGER.dump_task_table(buf)
+}
+
+/// Id of the least recently run task, see [`TaskManager::get_lru_task`].
+pub fn get_lru_task() -> Option<usize> {
+    TASK_MANAGER.get_lru_task()
+}
+
+/// Id of the task with the most syscalls, see [`TaskManager::busiest_task_by_syscalls`].
+pub fn busiest_task_by_syscalls() -> Option<usize> {
+    TASK_MANAGER.busiest_task_by_syscalls()
+}
+
+/// Fingerprint of the task states, see [`TaskManager::state_fingerprint`].
+pub fn state_fingerprint() -> u64 {
+    TASK_MANAGER.state_fingerprint()
+}
+
+/// Ids of the tasks in a status, see [`TaskManager::list_tasks_by_status`].
+pub fn list_tasks_by_status(status: TaskStatus) -> Vec<usize> {
+    TASK_MANAGER.list_tasks_by_status(status)
+}
+
+/// `UnInit` slots of loaded apps, see [`TaskManager::list_uninit_slots_below`].
+pub fn list_uninit_slots_below(num_app: usize) -> Vec<usize> {
+    TASK_MANAGER.list_uninit_slots_below(num_app)
+}
+
+/// The status of every slot, see [`TaskManager::snapshot_statuses`].
+pub fn snapshot_statuses() -> [TaskStatus; MAX_APP_NUM] {
+    TASK_MANAGER.snapshot_statuses()
+}
+
+/// Forecast the next dispatches, see [`TaskManager::predict_schedule`].
+pub fn predict_schedule(n: usize) -> Vec<usize> {
+    TASK_MANAGER.predict_schedule(n)
+}
+
+/// Set the current task's priority, see [`TaskManager::set_current_priority`].
+pub fn set_current_priority(priority: usize) -> usize {
+    TASK_MANAGER.set_current_priority(priority)
+}
+
+/// Swap the priorities of two tasks, see [`TaskManager::swap_priorities`].
+pub fn swap_priorities(id_a: usize, id_b: usize) -> bool {
+    TASK_MANAGER.swap_priorities(id_a, id_b)
+}
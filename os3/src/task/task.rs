@@ -1,8 +1,16 @@
 //! Types related to task management
 
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec::Vec;
 
-use super::TaskContext;
+use super::{StatusShares, TaskContext, TaskInfoSummary, TimeBreakdown, TraceDump, TraceEntry};
+use crate::config::{
+    DEFAULT_PRIORITY, DEFAULT_TIME_SLICE_MS, DEFAULT_WEIGHT, INTERACTIVE_IO_PERCENT,
+    INTERACTIVE_YIELD_PERCENT, MAX_PRIORITY, MAX_SYSCALL_NUM, MIN_PRIORITY, PRIORITY_CEILING,
+    SYSCALL_RATE_SAMPLES, SYSCALL_RATE_WINDOW_MS, TASK_NAME_LEN, TRACE_BUF_LEN, TRAP_CAUSE_NUM,
+};
+use crate::syscall::is_io_syscall;
+use crate::timer::time_delta;
 
 #[derive(Clone)]
 /// task control block structure
@@ -12,13 +20,724 @@ pub struct TaskControlBlock {
     // LAB1: Add whatever you need about the Task.
     pub syscall_times: BTreeMap<u16, u32>,
     pub init_time: usize,
+    pub priority: usize,
+    pub weight: usize,
+    pub tag: usize,
+    pub last_dispatch_time: usize,
+    /// start of the current stretch on the CPU, reset by a dispatch or [`Self::resume`]
+    pub run_started_at: usize,
+    /// time on the CPU in the stretches since the task last yielded or blocked
+    pub busy_us: usize,
+    pub user_time: usize,
+    pub user_entered_at: usize,
+    pub in_user: bool,
+    pub name: [u8; TASK_NAME_LEN],
+    pub name_len: usize,
+    pub dispatch_count: usize,
+    pub dispatch_gap_sum: usize,
+    pub yield_count: usize,
+    pub preempt_count: usize,
+    pub time_slice: usize,
+    pub slice_used: usize,
+    pub slice_stamp: usize,
+    pub trace_enabled: bool,
+    pub trace: VecDeque<TraceEntry>,
+    pub trace_dropped: usize,
+    pub wake_at: usize,
+    pub blocked_since: usize,
+    pub blocked_time_us: usize,
+    pub unknown_syscalls: usize,
+    pub info_overhead_us: usize,
+    pub max_continuous_run_us: usize,
+    pub status_changed_at: usize,
+    pub timer_irqs: usize,
+    pub min_share_permille: usize,
+    pub stats_reset_at: usize,
+    pub total_syscalls: u64,
+    pub cpu_budget_us: usize,
+    pub exit_reason: ExitReason,
+    pub prev_run: Option<TaskInfoSummary>,
+    pub trap_counts: [usize; TRAP_CAUSE_NUM],
+    pub base_priority: usize,
+    pub boost_depth: usize,
+    pub last_ran_at: usize,
+    pub deadline_at: usize,
+    pub deadline_missed: bool,
+    pub missed_deadlines: usize,
+    pub exit_time_us: usize,
+    pub slot_idle_us: usize,
+    pub skipped_count: usize,
+    pub recent_syscalls: VecDeque<usize>,
+    pub in_syscall_storm: bool,
+    pub related: Vec<usize>,
+    pub last_dispatch_gap: usize,
+    pub dispatch_jitter_x16: usize,
+    pub budget_extensions: usize,
+    pub cpu_time_us: usize,
+    pub on_cpu_since: usize,
+    pub counting_enabled: bool,
+    pub explicit_slice: bool,
+    pub syscall_switches: BTreeMap<u16, u32>,
+    pub bytes_written: usize,
 }
 
-#[derive(Copy, Clone, PartialEq)]
-/// task status: UnInit, Ready, Running, Exited
+impl TaskControlBlock {
+    /// An empty, `UnInit` slot with the default settings.
+    pub fn uninit() -> Self {
+        Self {
+            task_cx: TaskContext::zero_init(),
+            task_status: TaskStatus::UnInit,
+            syscall_times: BTreeMap::new(),
+            init_time: 0,
+            priority: DEFAULT_PRIORITY,
+            weight: DEFAULT_WEIGHT,
+            tag: 0,
+            last_dispatch_time: 0,
+            run_started_at: 0,
+            busy_us: 0,
+            user_time: 0,
+            user_entered_at: 0,
+            in_user: false,
+            name: [0; TASK_NAME_LEN],
+            name_len: 0,
+            dispatch_count: 0,
+            dispatch_gap_sum: 0,
+            yield_count: 0,
+            preempt_count: 0,
+            time_slice: DEFAULT_TIME_SLICE_MS * 1000,
+            slice_used: 0,
+            slice_stamp: 0,
+            trace_enabled: false,
+            trace: VecDeque::new(),
+            trace_dropped: 0,
+            wake_at: 0,
+            blocked_since: 0,
+            blocked_time_us: 0,
+            unknown_syscalls: 0,
+            info_overhead_us: 0,
+            max_continuous_run_us: 0,
+            status_changed_at: 0,
+            timer_irqs: 0,
+            min_share_permille: 0,
+            stats_reset_at: 0,
+            total_syscalls: 0,
+            cpu_budget_us: 0,
+            exit_reason: ExitReason::None,
+            prev_run: None,
+            trap_counts: [0; TRAP_CAUSE_NUM],
+            base_priority: DEFAULT_PRIORITY,
+            boost_depth: 0,
+            last_ran_at: 0,
+            deadline_at: 0,
+            deadline_missed: false,
+            missed_deadlines: 0,
+            exit_time_us: 0,
+            slot_idle_us: 0,
+            skipped_count: 0,
+            recent_syscalls: VecDeque::new(),
+            in_syscall_storm: false,
+            related: Vec::new(),
+            last_dispatch_gap: 0,
+            dispatch_jitter_x16: 0,
+            budget_extensions: 0,
+            cpu_time_us: 0,
+            on_cpu_since: 0,
+            counting_enabled: true,
+            explicit_slice: false,
+            syscall_switches: BTreeMap::new(),
+            bytes_written: 0,
+        }
+    }
+
+    /// Per-syscall counts of this task, indexed by syscall id.
+    pub fn syscall_snapshot(&self) -> [u32; MAX_SYSCALL_NUM] {
+        dense_counts(&self.syscall_times)
+    }
+
+    /// Per-syscall counts of the switches away from this task, indexed by syscall id.
+    pub fn syscall_switch_snapshot(&self) -> [u32; MAX_SYSCALL_NUM] {
+        dense_counts(&self.syscall_switches)
+    }
+
+    /// Check that the per-syscall counts add up to `total_syscalls`.
+    ///
+    /// Like the scheduler invariants this only checks with `debug_assertions`,
+    /// always `true` otherwise. A mismatch means some path counted a syscall
+    /// in one place only.
+    pub fn syscall_accounting_ok(&self) -> bool {
+        if !cfg!(debug_assertions) {
+            return true;
+        }
+        let sum: u64 = self.syscall_times.values().map(|&n| n as u64).sum();
+        sum == self.total_syscalls
+    }
+
+    /// Average gap in milliseconds between consecutive dispatches, 0 until dispatched twice.
+    pub fn avg_dispatch_interval_ms(&self) -> usize {
+        if self.dispatch_count < 2 {
+            return 0;
+        }
+        self.dispatch_gap_sum / (self.dispatch_count - 1) / 1000
+    }
+
+    /// Jitter in microseconds of the gaps between dispatches, 0 until two gaps were seen.
+    ///
+    /// This is the running estimate of RFC 3550: every gap moves it 1/16 of
+    /// the way towards how much that gap differs from the one before. It is
+    /// kept times 16 in `dispatch_jitter_x16` so integer rounding does not
+    /// stall it, and rounded to the nearest microsecond here.
+    pub fn dispatch_jitter_us(&self) -> usize {
+        (self.dispatch_jitter_x16 + 8) >> 4
+    }
+
+    /// Move to `status` at `now`, every status change goes through here.
+    pub fn set_status(&mut self, status: TaskStatus, now: usize) {
+        self.task_status = status;
+        self.status_changed_at = now;
+    }
+
+    /// Time on the CPU until `now` since this task last yielded or blocked.
+    ///
+    /// Preemptions do not count as giving up the CPU, whether another task
+    /// ran in between or this one was picked again right away.
+    pub fn busy_time_us(&self, now: usize) -> usize {
+        self.busy_us
+            .saturating_add(time_delta(now, self.run_started_at))
+    }
+
+    /// Make this task `Running` at `now`.
+    ///
+    /// This is the only place dispatch bookkeeping happens, so the first
+    /// dispatch stamps `init_time` exactly once whichever path picked the task.
+    pub fn dispatch(&mut self, now: usize) {
+        self.set_status(TaskStatus::Running, now);
+        if self.dispatch_count == 0 {
+            self.init_time = now;
+            self.stats_reset_at = now;
+            // a fresh task goes straight to `__restore`, skipping the end of `trap_handler`
+            self.in_user = true;
+            self.user_entered_at = now;
+        } else {
+            let gap = time_delta(now, self.last_dispatch_time);
+            self.dispatch_gap_sum += gap;
+            if self.dispatch_count >= 2 {
+                let d = gap.abs_diff(self.last_dispatch_gap);
+                let decay = self.dispatch_jitter_us();
+                self.dispatch_jitter_x16 = self.dispatch_jitter_x16 + d - decay;
+            }
+            self.last_dispatch_gap = gap;
+        }
+        self.dispatch_count += 1;
+        self.last_dispatch_time = now;
+        self.run_started_at = now;
+        self.on_cpu_since = now;
+        self.start_slice(now);
+    }
+
+    /// Keep this task on the CPU at `now`, it was picked again right after [`Self::end_run`].
+    ///
+    /// Not a dispatch, but a fresh stretch on the CPU and a fresh slice.
+    pub fn resume(&mut self, now: usize) {
+        self.set_status(TaskStatus::Running, now);
+        self.run_started_at = now;
+        self.start_slice(now);
+    }
+
+    /// End the stretch on the CPU that started at the last dispatch or resume.
+    ///
+    /// A `voluntary` end, anything but a preemption, also ends the busy
+    /// time, see [`Self::busy_time_us`].
+    pub fn end_run(&mut self, now: usize, voluntary: bool) {
+        let stretch = time_delta(now, self.run_started_at);
+        self.busy_us = if voluntary {
+            0
+        } else {
+            self.busy_us.saturating_add(stretch)
+        };
+        self.cpu_time_us += time_delta(now, self.on_cpu_since);
+        // a task picked again right away stays on the CPU from here
+        self.on_cpu_since = now;
+        self.last_ran_at = now;
+        self.max_continuous_run_us = self.max_continuous_run_us.max(stretch);
+    }
+
+    /// Take this task off the CPU into the waiting `status` at `now`.
+    pub fn block(&mut self, status: TaskStatus, now: usize) {
+        self.set_status(status, now);
+        self.blocked_since = now;
+    }
+
+    /// Make a `Blocked` or `Sleeping` task `Ready`, charging it the time waited until `now`.
+    pub fn wake(&mut self, now: usize) {
+        self.set_status(TaskStatus::Ready, now);
+        self.blocked_time_us += time_delta(now, self.blocked_since);
+    }
+
+    /// Start a fresh time slice at `now`.
+    pub fn start_slice(&mut self, now: usize) {
+        self.slice_used = 0;
+        self.slice_stamp = now;
+    }
+
+    /// Take `quantum_us` as the slice length unless the slice was set explicitly.
+    ///
+    /// Called before a new slice starts, so the slice under way keeps its length.
+    pub fn adopt_quantum(&mut self, quantum_us: usize) {
+        if !self.explicit_slice {
+            self.time_slice = quantum_us;
+        }
+    }
+
+    /// Charge the time since the last charge to the current slice.
+    ///
+    /// All arithmetic saturates: a clock going backwards charges nothing and
+    /// a huge delta leaves `slice_used` clamped at `time_slice`.
+    pub fn charge_slice(&mut self, now: usize) {
+        let delta = time_delta(now, self.slice_stamp);
+        self.slice_used = self.slice_used.saturating_add(delta).min(self.time_slice);
+        self.slice_stamp = now;
+    }
+
+    /// Microseconds left in the current slice, never below 0.
+    pub fn remaining_slice(&self) -> usize {
+        self.time_slice.saturating_sub(self.slice_used)
+    }
+
+    /// Append a syscall to the trace, overwriting the oldest entry once full.
+    pub fn trace_syscall(&mut self, now: usize, syscall_id: usize) {
+        if self.trace.len() == TRACE_BUF_LEN {
+            self.trace.pop_front();
+            self.trace_dropped += 1;
+        }
+        self.trace.push_back(TraceEntry {
+            time_us: now,
+            syscall_id,
+        });
+    }
+
+    /// Remember a syscall at `now` for [`Self::syscall_rate_per_sec`].
+    ///
+    /// Only the last `SYSCALL_RATE_SAMPLES` syscalls of the last
+    /// `SYSCALL_RATE_WINDOW_MS` are kept.
+    pub fn note_syscall_time(&mut self, now: usize) {
+        if self.recent_syscalls.len() == SYSCALL_RATE_SAMPLES {
+            self.recent_syscalls.pop_front();
+        }
+        self.recent_syscalls.push_back(now);
+        let window_start = now.saturating_sub(SYSCALL_RATE_WINDOW_MS * 1000);
+        while self
+            .recent_syscalls
+            .front()
+            .map_or(false, |&t| t < window_start)
+        {
+            self.recent_syscalls.pop_front();
+        }
+    }
+
+    /// Syscalls per second over the last `SYSCALL_RATE_WINDOW_MS` at `now`.
+    ///
+    /// With the ring full the window holds more syscalls than we kept, so
+    /// the rate is taken over the span of the kept ones instead.
+    pub fn syscall_rate_per_sec(&self, now: usize) -> usize {
+        let window_us = SYSCALL_RATE_WINDOW_MS * 1000;
+        let window_start = now.saturating_sub(window_us);
+        let n = self
+            .recent_syscalls
+            .iter()
+            .filter(|&&t| t >= window_start)
+            .count();
+        let span_us = match self.recent_syscalls.front() {
+            Some(&oldest) if n == SYSCALL_RATE_SAMPLES => time_delta(now, oldest).max(1),
+            _ => window_us,
+        };
+        n * 1_000_000 / span_us
+    }
+
+    /// Copy out the syscall trace.
+    pub fn trace_dump(&self) -> TraceDump {
+        let mut dump = TraceDump {
+            dropped: self.trace_dropped,
+            len: self.trace.len(),
+            entries: [TraceEntry::default(); TRACE_BUF_LEN],
+        };
+        for (dst, src) in dump.entries.iter_mut().zip(self.trace.iter()) {
+            *dst = *src;
+        }
+        dump
+    }
+
+    /// Clear the syscall counts, remembering `now` as the reset time.
+    pub fn reset_syscall_stats(&mut self, now: usize) {
+        self.syscall_times.clear();
+        self.syscall_switches.clear();
+        self.bytes_written = 0;
+        self.total_syscalls = 0;
+        self.unknown_syscalls = 0;
+        self.stats_reset_at = now;
+    }
+
+    /// Whether the user time charged so far has reached `cpu_budget_us`, never without a budget.
+    pub fn is_over_budget(&self) -> bool {
+        self.cpu_budget_us != 0 && self.user_time >= self.cpu_budget_us
+    }
+
+    /// Split the time since the first dispatch at `now`, all zero if never dispatched.
+    ///
+    /// Time on the CPU and blocked time include the stretch under way. The
+    /// kernel part is what is left of the CPU time after the user time, and
+    /// the ready wait what is left of the total.
+    pub fn time_breakdown(&self, now: usize) -> TimeBreakdown {
+        if self.dispatch_count == 0 {
+            return TimeBreakdown {
+                total_us: 0,
+                kernel_us: 0,
+                user_us: 0,
+                ready_wait_us: 0,
+                blocked_us: 0,
+            };
+        }
+        let total_us = time_delta(now, self.init_time);
+        let mut cpu_us = self.cpu_time_us;
+        let mut blocked_us = self.blocked_time_us;
+        match self.task_status {
+            TaskStatus::Running => cpu_us += time_delta(now, self.on_cpu_since),
+            TaskStatus::Blocked | TaskStatus::Sleeping => {
+                blocked_us += time_delta(now, self.blocked_since)
+            }
+            _ => {}
+        }
+        let mut user_us = self.user_time;
+        if self.in_user {
+            user_us += time_delta(now, self.user_entered_at);
+        }
+        TimeBreakdown {
+            total_us,
+            kernel_us: cpu_us.saturating_sub(user_us),
+            user_us,
+            ready_wait_us: total_us.saturating_sub(cpu_us).saturating_sub(blocked_us),
+            blocked_us,
+        }
+    }
+
+    /// Split the lifetime of this task between the statuses, see [`StatusShares`].
+    ///
+    /// The lifetime runs from the first dispatch to the exit of an `Exited`
+    /// or the freeze of a `Frozen` task and to `now` for one still alive. Ready gets the rounding rest
+    /// so the parts add up to 1000.
+    pub fn status_shares(&self, now: usize) -> StatusShares {
+        let end = if self.task_status.is_terminal() {
+            self.exit_time_us
+        } else {
+            now
+        };
+        let breakdown = self.time_breakdown(end);
+        if breakdown.total_us == 0 {
+            return StatusShares {
+                running_permille: 0,
+                ready_permille: 0,
+                blocked_permille: 0,
+            };
+        }
+        let permille = |us: usize| (us as u64 * 1000 / breakdown.total_us as u64).min(1000) as u32;
+        let running_permille = permille(breakdown.kernel_us + breakdown.user_us);
+        let blocked_permille = permille(breakdown.blocked_us).min(1000 - running_permille);
+        StatusShares {
+            running_permille,
+            ready_permille: 1000 - running_permille - blocked_permille,
+            blocked_permille,
+        }
+    }
+
+    /// Whether this task behaves interactively or like a batch job so far.
+    ///
+    /// A task is interactive if at least `INTERACTIVE_YIELD_PERCENT` of the
+    /// times it left the CPU on a yield or preemption were yields, or at
+    /// least `INTERACTIVE_IO_PERCENT` of its syscalls did I/O. Until it
+    /// yielded or was preempted once it is unclassified.
+    pub fn classify(&self) -> TaskClass {
+        let switches = self.yield_count + self.preempt_count;
+        if switches == 0 {
+            return TaskClass::Unclassified;
+        }
+        let io_syscalls: u64 = self
+            .syscall_times
+            .iter()
+            .filter(|(&id, _)| is_io_syscall(id as usize))
+            .map(|(_, &n)| n as u64)
+            .sum();
+        let yields_often = self.yield_count * 100 >= INTERACTIVE_YIELD_PERCENT * switches;
+        let does_io = self.total_syscalls != 0
+            && io_syscalls * 100 >= INTERACTIVE_IO_PERCENT as u64 * self.total_syscalls;
+        if yields_often || does_io {
+            TaskClass::Interactive
+        } else {
+            TaskClass::Batch
+        }
+    }
+
+    /// The key stats of this run, kept across a restart as `prev_run`.
+    pub fn summary(&self) -> TaskInfoSummary {
+        TaskInfoSummary {
+            run_time_ms: time_delta(self.status_changed_at, self.init_time) / 1000,
+            user_time_us: self.user_time,
+            info_overhead_us: self.info_overhead_us,
+            dispatches: self.dispatch_count,
+            syscalls: self.total_syscalls as usize,
+            exit_reason: self.exit_reason,
+        }
+    }
+
+    /// Record a restart relationship with task `id`, keeping `related` sorted and unique.
+    pub fn relate(&mut self, id: usize) {
+        if let Err(pos) = self.related.binary_search(&id) {
+            self.related.insert(pos, id);
+        }
+    }
+
+    /// Enter a boosted critical region, raising `priority` to at least `PRIORITY_CEILING`.
+    ///
+    /// Regions nest, only the outermost one saves `base_priority`.
+    pub fn boost_begin(&mut self) {
+        if self.boost_depth == 0 {
+            self.base_priority = self.priority;
+            self.priority = clamp_priority(self.priority.max(PRIORITY_CEILING));
+        }
+        self.boost_depth += 1;
+    }
+
+    /// Leave a boosted critical region, restoring `base_priority` once the outermost one ends.
+    ///
+    /// Returns `false`, changing nothing, if no region is open.
+    pub fn boost_end(&mut self) -> bool {
+        if self.boost_depth == 0 {
+            return false;
+        }
+        self.boost_depth -= 1;
+        if self.boost_depth == 0 {
+            self.priority = self.base_priority;
+        }
+        true
+    }
+
+    /// Count the deadline as missed if it passed before `now` and the task still runs.
+    ///
+    /// A deadline is counted once, until `sys_set_deadline` declares a new one.
+    pub fn check_deadline(&mut self, now: usize) {
+        if self.deadline_at == 0 || self.deadline_missed || self.deadline_at >= now {
+            return;
+        }
+        if self.task_status.is_terminal() {
+            return;
+        }
+        self.deadline_missed = true;
+        self.missed_deadlines += 1;
+    }
+
+    /// The priority without any boost.
+    pub fn own_priority(&self) -> usize {
+        if self.boost_depth == 0 {
+            self.priority
+        } else {
+            self.base_priority
+        }
+    }
+
+    /// Set the base priority to `priority`, clamped by [`clamp_priority`].
+    ///
+    /// Inside a boosted region only `base_priority` changes for good, the
+    /// task keeps running at no less than the ceiling until the region ends.
+    pub fn set_priority(&mut self, priority: usize) {
+        let priority = clamp_priority(priority);
+        if self.boost_depth == 0 {
+            self.priority = priority;
+        } else {
+            self.base_priority = priority;
+            self.priority = clamp_priority(priority.max(PRIORITY_CEILING));
+        }
+    }
+
+    /// Set the display name, truncated to `TASK_NAME_LEN` bytes.
+    pub fn set_name(&mut self, name: &[u8]) {
+        let len = name.len().min(TASK_NAME_LEN);
+        self.name[..len].copy_from_slice(&name[..len]);
+        self.name_len = len;
+    }
+}
+
+/// Expand sparse per-syscall `counts` into an array indexed by syscall id.
+fn dense_counts(counts: &BTreeMap<u16, u32>) -> [u32; MAX_SYSCALL_NUM] {
+    let mut count = [0u32; MAX_SYSCALL_NUM];
+    for (key, val) in counts.iter() {
+        count[*key as usize] = *val;
+    }
+    count
+}
+
+/// Clamp `priority` into `MIN_PRIORITY..=MAX_PRIORITY`.
+///
+/// Every path that sets a priority goes through here, so a task can never
+/// hold a priority outside the range.
+pub fn clamp_priority(priority: usize) -> usize {
+    priority.clamp(MIN_PRIORITY, MAX_PRIORITY)
+}
+
+/// a simple test for the dispatch jitter estimate
+pub fn dispatch_jitter_test() {
+    // gaps alternating between 1000us and 1300us always differ by 300us
+    let mut tcb = TaskControlBlock::uninit();
+    let mut now = 0;
+    for i in 0..200 {
+        tcb.dispatch(now);
+        now += if i % 2 == 0 { 1000 } else { 1300 };
+    }
+    assert_eq!(tcb.dispatch_jitter_us(), 300);
+    info!("dispatch_jitter_test passed!");
+}
+
+#[derive(Copy, Clone)]
+/// per-app configuration applied when the task table is built
+pub struct AppDescriptor {
+    pub priority: usize,
+    pub weight: usize,
+    pub tag: usize,
+}
+
+impl Default for AppDescriptor {
+    fn default() -> Self {
+        Self {
+            priority: DEFAULT_PRIORITY,
+            weight: DEFAULT_WEIGHT,
+            tag: 0,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+/// why the kernel switched tasks
+pub enum SwitchReason {
+    /// the very first switch at boot, from no task
+    FirstRun,
+    Yield,
+    Preempt,
+    Exit,
+    /// the task went to sleep or waits on a barrier
+    Block,
+    /// `sys_switch_to` picked the next task
+    Directed,
+    /// the kernel froze a task that ran too long without giving up the CPU
+    Freeze,
+}
+
+#[derive(Copy, Clone, Debug)]
+/// which rule of the scheduling policy picked the next task
+pub enum SelectReason {
+    /// task 0 runs first at boot, before any policy applies
+    Initial,
+    /// `sys_switch_to` asked for the task
+    Directed,
+    /// the task is next in a schedule being replayed
+    Replay,
+    /// the task is furthest below its guaranteed share
+    MinShare,
+    /// the task has the earliest `deadline_at`
+    EarliestDeadline,
+    /// the task is the next runnable one after the current task
+    RoundRobin,
+    /// no other task was runnable, so the fallback task runs
+    Fallback,
+}
+
+#[derive(Copy, Clone, Debug)]
+/// trap causes counted per task, the discriminant indexes `trap_counts`
+pub enum TrapCause {
+    Syscall,
+    Timer,
+    /// store fault or store page fault
+    PageFault,
+    IllegalInstruction,
+    /// anything else, which the trap handler does not support
+    Other,
+}
+
+impl TrapCause {
+    /// The cause with discriminant `n`, as passed in from user space.
+    pub fn from_usize(n: usize) -> Option<Self> {
+        match n {
+            0 => Some(TrapCause::Syscall),
+            1 => Some(TrapCause::Timer),
+            2 => Some(TrapCause::PageFault),
+            3 => Some(TrapCause::IllegalInstruction),
+            4 => Some(TrapCause::Other),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+/// why an `Exited` task exited, or a `Frozen` one stopped
+pub enum ExitReason {
+    /// the task has not exited
+    None,
+    /// the task exited on its own
+    Completed,
+    /// the kernel reaped the task once it used up its `cpu_budget_us`
+    BudgetExceeded,
+    /// the kernel killed the task for a fault
+    Crashed,
+    /// the kernel froze the task for running too long without giving up the
+    /// CPU, it is `Frozen` rather than `Exited`
+    Frozen,
+}
+
+#[derive(Copy, Clone, Debug)]
+/// how a task uses the CPU, see [`TaskControlBlock::classify`]
+pub enum TaskClass {
+    /// the task has not yielded or been preempted yet
+    Unclassified,
+    /// mostly gives up the CPU on its own or does I/O
+    Interactive,
+    /// mostly runs until preempted
+    Batch,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// task status: UnInit, Ready, Running, Exited, Blocked, Sleeping, Frozen
 pub enum TaskStatus {
     UnInit,
     Ready,
     Running,
     Exited,
+    Blocked,
+    Sleeping,
+    /// taken off the CPU for good as a runaway, its state left for inspection
+    Frozen,
+}
+
+impl TaskStatus {
+    /// The status with discriminant `n`, as passed in from user space.
+    pub fn from_usize(n: usize) -> Option<Self> {
+        match n {
+            0 => Some(TaskStatus::UnInit),
+            1 => Some(TaskStatus::Ready),
+            2 => Some(TaskStatus::Running),
+            3 => Some(TaskStatus::Exited),
+            4 => Some(TaskStatus::Blocked),
+            5 => Some(TaskStatus::Sleeping),
+            6 => Some(TaskStatus::Frozen),
+            _ => None,
+        }
+    }
+
+    /// Whether the scheduler may dispatch a task in this status.
+    ///
+    /// Only `Ready` is. Any status added later stays off the CPU unless it
+    /// is listed here on purpose.
+    pub fn is_runnable(self) -> bool {
+        matches!(self, TaskStatus::Ready)
+    }
+
+    /// Whether a task in this status is done for good, `Exited` or `Frozen`.
+    pub fn is_terminal(self) -> bool {
+        matches!(self, TaskStatus::Exited | TaskStatus::Frozen)
+    }
 }
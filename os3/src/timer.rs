@@ -2,22 +2,56 @@
 
 use crate::config::CLOCK_FREQ;
 use crate::sbi::set_timer;
+use core::sync::atomic::{AtomicUsize, Ordering};
 use riscv::register::time;
 
 const TICKS_PER_SEC: usize = 100;
 const MICRO_PER_SEC: usize = 1_000_000;
 const MILLI_PER_SEC: usize = 1_000;
 
+/// time deltas that came out negative, see [`time_delta`]
+static TIME_ANOMALIES: AtomicUsize = AtomicUsize::new(0);
+
 /// read the `mtime` register
 pub fn get_time() -> usize {
     time::read()
 }
 
+/// get the raw tick count of the `time` CSR, unconverted
+///
+/// It counts at `CLOCK_FREQ` Hz. [`get_time_us`] divides it down, so the two
+/// differ by that factor and this keeps the sub-microsecond bits.
+pub fn get_time_ticks() -> usize {
+    get_time()
+}
+
 /// get current time in microseconds
 pub fn get_time_us() -> usize {
     time::read() / (CLOCK_FREQ / MICRO_PER_SEC)
 }
 
+/// get the microseconds elapsed since `epoch_us`, 0 if it lies in the future
+pub fn time_since(epoch_us: usize) -> usize {
+    get_time_us().saturating_sub(epoch_us)
+}
+
+/// get the microseconds from `earlier` to `now`, both stamps of `get_time_us`
+///
+/// The clock should never go backwards, so `earlier` past `now` is a timer
+/// problem: the delta is 0 and the anomaly is counted for [`time_anomaly_count`].
+pub fn time_delta(now: usize, earlier: usize) -> usize {
+    if now < earlier {
+        TIME_ANOMALIES.fetch_add(1, Ordering::Relaxed);
+    }
+    now.saturating_sub(earlier)
+}
+
+/// get how many time deltas came out negative since boot
+pub fn time_anomaly_count() -> usize {
+    TIME_ANOMALIES.load(Ordering::Relaxed)
+}
+
+#[allow(unused)]
 /// get current time in milliseconds
 pub fn get_time_ms() -> usize {
     time::read() / (CLOCK_FREQ / MILLI_PER_SEC)
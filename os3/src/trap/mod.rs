@@ -15,7 +15,12 @@
 mod context;
 
 use crate::syscall::syscall;
-use crate::task::{exit_current_and_run_next, suspend_current_and_run_next, increase_syscall_count};
+use crate::task::{
+    count_current_trap, crash_current_and_run_next, current_task_id, freeze_current_and_run_next,
+    increase_syscall_count, is_current_over_budget, is_current_runaway, mark_enter_user,
+    mark_exit_user, preempt_current_and_run_next, reap_current_and_run_next, record_syscall,
+    tick_current_slice, TrapCause,
+};
 use crate::timer::set_next_trigger;
 use riscv::register::{
     mtvec::TrapMode,
@@ -45,25 +50,45 @@ pub fn enable_timer_interrupt() {
 #[no_mangle]
 /// handle an interrupt, exception, or system call from user space
 pub fn trap_handler(cx: &mut TrapContext) -> &mut TrapContext {
+    mark_exit_user();
     let scause = scause::read(); // get trap cause
     let stval = stval::read(); // get extra value
+    count_current_trap(trap_cause(scause.cause()));
     match scause.cause() {
         Trap::Exception(Exception::UserEnvCall) => {
-            increase_syscall_count(cx.x[17] as u16);
             cx.sepc += 4;
-            cx.x[10] = syscall(cx.x[17], [cx.x[10], cx.x[11], cx.x[12]]) as usize;
+            let syscall_id = cx.x[17];
+            if increase_syscall_count(syscall_id) {
+                let result = syscall(syscall_id, [cx.x[10], cx.x[11], cx.x[12]]);
+                record_syscall(syscall_id, result);
+                cx.x[10] = result as usize;
+            } else {
+                warn!("[kernel] Unknown syscall_id: {}", cx.x[17]);
+                cx.x[10] = -1isize as usize;
+            }
         }
         Trap::Exception(Exception::StoreFault) | Trap::Exception(Exception::StorePageFault) => {
             error!("[kernel] PageFault in application, bad addr = {:#x}, bad instruction = {:#x}, core dumped.", stval, cx.sepc);
-            exit_current_and_run_next();
+            crash_current_and_run_next();
         }
         Trap::Exception(Exception::IllegalInstruction) => {
             error!("[kernel] IllegalInstruction in application, core dumped.");
-            exit_current_and_run_next();
+            crash_current_and_run_next();
         }
         Trap::Interrupt(Interrupt::SupervisorTimer) => {
             set_next_trigger();
-            suspend_current_and_run_next();
+            let slice_used_up = tick_current_slice();
+            if is_current_over_budget() {
+                info!(
+                    "[kernel] task {} exceeded its CPU budget, reaped.",
+                    current_task_id()
+                );
+                reap_current_and_run_next();
+            } else if is_current_runaway() {
+                freeze_current_and_run_next();
+            } else if slice_used_up {
+                preempt_current_and_run_next();
+            }
         }
         _ => {
             panic!(
@@ -73,7 +98,21 @@ pub fn trap_handler(cx: &mut TrapContext) -> &mut TrapContext {
             );
         }
     }
+    mark_enter_user();
     cx
 }
 
+/// Map a RISC-V trap cause to the cause counted in `trap_counts`.
+fn trap_cause(cause: Trap) -> TrapCause {
+    match cause {
+        Trap::Exception(Exception::UserEnvCall) => TrapCause::Syscall,
+        Trap::Interrupt(Interrupt::SupervisorTimer) => TrapCause::Timer,
+        Trap::Exception(Exception::StoreFault) | Trap::Exception(Exception::StorePageFault) => {
+            TrapCause::PageFault
+        }
+        Trap::Exception(Exception::IllegalInstruction) => TrapCause::IllegalInstruction,
+        _ => TrapCause::Other,
+    }
+}
+
 pub use context::TrapContext;
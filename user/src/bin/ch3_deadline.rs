@@ -0,0 +1,30 @@
+#![no_std]
+#![no_main]
+
+extern crate user_lib;
+
+use user_lib::{
+    get_current_task_brief, println, record_schedule, set_deadline, stop_recording, yield_,
+};
+
+/// With the only deadline in the system, every yield picks this task again
+/// over the tasks without one.
+#[no_mangle]
+pub fn main() -> usize {
+    let (id, _) = get_current_task_brief();
+    assert_eq!(0, set_deadline(1000));
+    assert_eq!(0, record_schedule());
+    for _ in 0..10 {
+        yield_();
+    }
+    let mut schedule = [0usize; 16];
+    let n = stop_recording(&mut schedule);
+    assert!(n >= 0);
+    // picking the caller again is no switch, so nothing else may show up
+    for &picked in &schedule[..(n as usize).min(schedule.len())] {
+        assert_eq!(id, picked);
+    }
+    assert_eq!(0, set_deadline(0));
+    println!("Test deadline OK!");
+    0
+}
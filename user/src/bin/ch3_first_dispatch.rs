@@ -0,0 +1,29 @@
+#![no_std]
+#![no_main]
+
+extern crate user_lib;
+
+use user_lib::{get_time, println, task_info, yield_, TaskInfo, TaskStatus};
+
+/// Yield and resume a few times, the first dispatch must stay where it was.
+#[no_mangle]
+pub fn main() -> usize {
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    // `time` counts from the first dispatch, so this is when that happened
+    let first_dispatch = get_time() as usize - info.time;
+    let mut prev = info.time;
+    for _ in 0..10 {
+        yield_();
+        assert_eq!(0, task_info(&info));
+        let now = get_time() as usize;
+        assert_eq!(TaskStatus::Running, info.status);
+        assert!(info.time >= prev);
+        // a second stamp would move the first dispatch forward, allow 1ms of rounding
+        let dispatch = now - info.time;
+        assert!(dispatch + 1 >= first_dispatch && dispatch <= first_dispatch + 1);
+        prev = info.time;
+    }
+    println!("Test first dispatch OK!");
+    0
+}
@@ -0,0 +1,61 @@
+#![no_std]
+#![no_main]
+
+extern crate user_lib;
+
+use user_lib::{
+    dump_task_table, get_current_task_brief, get_quantum_time, get_time, println, set_preemption,
+    task_stats, yield_, TaskDumpEntry, TaskStats,
+};
+
+/// `TaskStatus` discriminants of slots that never run again
+const UNINIT: usize = 0;
+const EXITED: usize = 3;
+const FROZEN: usize = 6;
+
+fn spin_ms(ms: isize) {
+    let start = get_time();
+    while get_time() - start < ms {}
+}
+
+/// Whether every slot but `id` is done, and how often `id` was dispatched.
+fn others_done(id: usize) -> (bool, usize) {
+    let mut table = [TaskDumpEntry::default(); 16];
+    let n = dump_task_table(&mut table);
+    assert!(n >= 0);
+    let rows = &table[..n as usize];
+    let done = rows
+        .iter()
+        .all(|e| e.id == id || e.status == UNINIT || e.status == EXITED || e.status == FROZEN);
+    let dispatches = rows.iter().find(|e| e.id == id).unwrap().dispatches;
+    (done, dispatches)
+}
+
+/// Once this app runs alone, a yield picks it again without a switch but
+/// still starts a new run stretch.
+#[no_mangle]
+pub fn main() -> usize {
+    let (id, _) = get_current_task_brief();
+    // until the other apps are done a yield may well switch to them
+    while !others_done(id).0 {
+        yield_();
+    }
+    let (_, before) = others_done(id);
+    assert_eq!(0, set_preemption(false));
+    for _ in 0..3 {
+        spin_ms(20);
+        yield_();
+        // the time before the yield must not carry over
+        assert!(get_quantum_time() < 10_000);
+    }
+    assert_eq!(0, set_preemption(true));
+    // picking the caller again is no dispatch
+    assert_eq!(before, others_done(id).1);
+    let stats = TaskStats::new();
+    assert_eq!(0, task_stats(id, &stats));
+    // each stretch was about 20ms, a stale start would add them up
+    assert!(stats.max_continuous_run_us >= 20_000);
+    assert!(stats.max_continuous_run_us < 35_000);
+    println!("Test lone yield OK!");
+    0
+}
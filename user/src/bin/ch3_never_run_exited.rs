@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+
+extern crate user_lib;
+
+use user_lib::{
+    dump_switch_trace, list_tasks_by_status, println, switch_to, task_stats, yield_,
+    SwitchTraceDump, TaskStats, TaskStatus,
+};
+
+/// No switch may go to a task after it exited, whichever slot comes next,
+/// and neither may a directed one.
+#[no_mangle]
+pub fn main() -> usize {
+    // give the scheduler plenty of chances to pick a wrong slot
+    for _ in 0..32 {
+        yield_();
+    }
+    let mut dump = SwitchTraceDump::new();
+    assert_eq!(0, dump_switch_trace(&mut dump));
+    let mut exited = [0usize; 16];
+    let n = list_tasks_by_status(TaskStatus::Exited, &mut exited);
+    assert!(n >= 0);
+    let stats = TaskStats::new();
+    for &id in &exited[..n as usize] {
+        assert_eq!(0, task_stats(id, &stats));
+        for record in dump.records() {
+            assert!(record.to_id != id || record.time_us <= stats.status_changed_at);
+        }
+        assert_eq!(-1, switch_to(id));
+    }
+    println!("Test never run exited OK!");
+    0
+}
@@ -0,0 +1,46 @@
+#![no_std]
+#![no_main]
+
+extern crate user_lib;
+
+use user_lib::{
+    dump_task_table, get_current_task_brief, println, priority_boost_begin, priority_boost_end,
+    set_priority, TaskDumpEntry,
+};
+
+/// `MAX_PRIORITY` of the kernel config
+const MAX_PRIORITY: usize = 64;
+
+fn priority_of(id: usize) -> usize {
+    let mut table = [TaskDumpEntry::default(); 16];
+    let n = dump_task_table(&mut table);
+    assert!(n >= 0);
+    let entry = table[..n as usize].iter().find(|e| e.id == id).unwrap();
+    entry.priority
+}
+
+/// Every priority setter ends up in `MIN_PRIORITY..=MAX_PRIORITY`.
+#[no_mangle]
+pub fn main() -> usize {
+    let (id, _) = get_current_task_brief();
+    assert_eq!(10, set_priority(10));
+    assert_eq!(10, priority_of(id));
+
+    // below the range is refused and changes nothing
+    assert_eq!(-1, set_priority(1));
+    assert_eq!(-1, set_priority(-10));
+    assert_eq!(10, priority_of(id));
+
+    // above the range is accepted but the task holds the maximum
+    assert_eq!(isize::MAX, set_priority(isize::MAX));
+    assert_eq!(MAX_PRIORITY, priority_of(id));
+
+    // the boost ceiling goes through the same clamp
+    assert_eq!(10, set_priority(10));
+    assert_eq!(0, priority_boost_begin());
+    assert_eq!(MAX_PRIORITY, priority_of(id));
+    assert_eq!(0, priority_boost_end());
+    assert_eq!(10, priority_of(id));
+    println!("Test priority clamp OK!");
+    0
+}
@@ -0,0 +1,25 @@
+#![no_std]
+#![no_main]
+
+extern crate user_lib;
+
+use user_lib::{println, sleep_us, sys_get_time, time_since, TimeVal};
+
+fn get_time_us() -> usize {
+    let time = TimeVal::new();
+    assert_eq!(0, sys_get_time(&time, 0));
+    time.sec * 1_000_000 + time.usec
+}
+
+/// A sub-millisecond sleep may end late, on a timer tick, but never early.
+#[no_mangle]
+pub fn main() -> usize {
+    for _ in 0..5 {
+        let start = get_time_us();
+        assert_eq!(0, sleep_us(500));
+        let slept = time_since(start);
+        assert!(slept >= 500, "woken after {} us", slept);
+    }
+    println!("Test sleep us OK!");
+    0
+}
@@ -0,0 +1,29 @@
+#![no_std]
+#![no_main]
+
+extern crate user_lib;
+
+use user_lib::{
+    get_current_task_brief, get_remaining_slice, get_time, println, set_preemption, set_time_slice,
+};
+
+/// Run far past a 1ms slice and check the remaining slice stops at 0.
+#[no_mangle]
+pub fn main() -> usize {
+    let (id, _) = get_current_task_brief();
+    assert_eq!(0, set_preemption(false));
+    assert_eq!(0, set_time_slice(id, 1));
+    let start = get_time();
+    while get_time() - start < 50 {}
+    // 50 times the slice has been charged, it must not wrap around
+    assert_eq!(0, get_remaining_slice());
+
+    // the largest slice neither overflows nor comes back negative
+    assert_eq!(0, set_time_slice(id, usize::MAX));
+    assert!(get_remaining_slice() > 0);
+
+    assert_eq!(0, set_time_slice(id, 10));
+    assert_eq!(0, set_preemption(true));
+    println!("Test slice saturate OK!");
+    0
+}
@@ -98,12 +98,31 @@ impl TimeVal {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum TaskStatus {
     UnInit,
     Ready,
     Running,
     Exited,
+    Blocked,
+    Sleeping,
+    Frozen,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ExitReason {
+    None,
+    Completed,
+    BudgetExceeded,
+    Crashed,
+    Frozen,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TaskClass {
+    Unclassified,
+    Interactive,
+    Batch,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -112,7 +131,27 @@ pub struct SyscallInfo {
     pub times: usize,
 }
 
-const MAX_SYSCALL_NUM: usize = 500;
+pub const MAX_SYSCALL_NUM: usize = 500;
+
+/// Index into `TaskStats::trap_counts`
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TrapCause {
+    Syscall,
+    Timer,
+    PageFault,
+    IllegalInstruction,
+    Other,
+}
+
+pub const TRAP_CAUSE_NUM: usize = 5;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct StatusShares {
+    pub running_permille: u32,
+    pub ready_permille: u32,
+    pub blocked_permille: u32,
+}
 
 #[derive(Debug)]
 pub struct TaskInfo {
@@ -131,6 +170,240 @@ impl TaskInfo {
     }
 }
 
+impl Default for TaskInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The stats beyond `TaskInfo`, see the kernel's `TaskStats`
+#[repr(C)]
+#[derive(Debug)]
+pub struct TaskStats {
+    pub avg_dispatch_interval_ms: usize,
+    pub blocked_time_us: usize,
+    pub unknown_syscalls: usize,
+    pub info_overhead_us: usize,
+    pub max_continuous_run_us: usize,
+    pub status_changed_at: usize,
+    pub timer_irqs: usize,
+    pub stats_reset_at: usize,
+    pub exit_reason: ExitReason,
+    pub cpu_share_permille: u32,
+    pub trap_counts: [usize; TRAP_CAUSE_NUM],
+    pub missed_deadlines: usize,
+    pub skipped_count: usize,
+    pub syscall_rate_per_sec: usize,
+    pub dispatch_jitter_us: usize,
+    pub budget_extensions: usize,
+    pub class: TaskClass,
+    pub status_shares: StatusShares,
+    pub syscall_switches: [u32; MAX_SYSCALL_NUM],
+    pub bytes_written: usize,
+}
+
+impl TaskStats {
+    pub fn new() -> Self {
+        TaskStats {
+            avg_dispatch_interval_ms: 0,
+            blocked_time_us: 0,
+            unknown_syscalls: 0,
+            info_overhead_us: 0,
+            max_continuous_run_us: 0,
+            status_changed_at: 0,
+            timer_irqs: 0,
+            stats_reset_at: 0,
+            exit_reason: ExitReason::None,
+            cpu_share_permille: 0,
+            trap_counts: [0; TRAP_CAUSE_NUM],
+            missed_deadlines: 0,
+            skipped_count: 0,
+            syscall_rate_per_sec: 0,
+            dispatch_jitter_us: 0,
+            budget_extensions: 0,
+            class: TaskClass::Unclassified,
+            status_shares: StatusShares::default(),
+            syscall_switches: [0; MAX_SYSCALL_NUM],
+            bytes_written: 0,
+        }
+    }
+}
+
+impl Default for TaskStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct TaskInfoSummary {
+    pub run_time_ms: usize,
+    pub user_time_us: usize,
+    pub info_overhead_us: usize,
+    pub dispatches: usize,
+    pub syscalls: usize,
+    pub exit_reason: ExitReason,
+}
+
+impl TaskInfoSummary {
+    pub fn new() -> Self {
+        TaskInfoSummary {
+            run_time_ms: 0,
+            user_time_us: 0,
+            info_overhead_us: 0,
+            dispatches: 0,
+            syscalls: 0,
+            exit_reason: ExitReason::None,
+        }
+    }
+}
+
+impl Default for TaskInfoSummary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One row of `dump_task_table`, see the kernel's `TaskDumpEntry` for the layout
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TaskDumpEntry {
+    pub id: usize,
+    pub status: usize,
+    pub priority: usize,
+    pub time_ms: usize,
+    pub user_time_us: usize,
+    pub dispatches: usize,
+}
+
+/// Where the time of the current task went, see the kernel's `TimeBreakdown`
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TimeBreakdown {
+    pub total_us: usize,
+    pub kernel_us: usize,
+    pub user_us: usize,
+    pub ready_wait_us: usize,
+    pub blocked_us: usize,
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct SwitchLatency {
+    pub samples: usize,
+    pub min_us: usize,
+    pub max_us: usize,
+    pub mean_us: usize,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SelectReason {
+    Initial,
+    Directed,
+    Replay,
+    MinShare,
+    EarliestDeadline,
+    RoundRobin,
+    Fallback,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SwitchReason {
+    FirstRun,
+    Yield,
+    Preempt,
+    Exit,
+    Block,
+    Directed,
+    Freeze,
+}
+
+pub const SWITCH_TRACE_LEN: usize = 64;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct SwitchRecord {
+    pub time_us: usize,
+    pub from_id: usize,
+    pub to_id: usize,
+    pub reason: SwitchReason,
+    pub selected_by: SelectReason,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct SwitchTraceDump {
+    pub dropped: usize,
+    pub len: usize,
+    pub records: [SwitchRecord; SWITCH_TRACE_LEN],
+}
+
+impl SwitchTraceDump {
+    pub fn new() -> Self {
+        SwitchTraceDump {
+            dropped: 0,
+            len: 0,
+            records: [SwitchRecord {
+                time_us: 0,
+                from_id: 0,
+                to_id: 0,
+                reason: SwitchReason::FirstRun,
+                selected_by: SelectReason::Initial,
+            }; SWITCH_TRACE_LEN],
+        }
+    }
+
+    /// The recorded switches, oldest first.
+    pub fn records(&self) -> &[SwitchRecord] {
+        &self.records[..self.len]
+    }
+}
+
+impl Default for SwitchTraceDump {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub const TRACE_BUF_LEN: usize = 64;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TraceEntry {
+    pub time_us: usize,
+    pub syscall_id: usize,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct TraceDump {
+    pub dropped: usize,
+    pub len: usize,
+    pub entries: [TraceEntry; TRACE_BUF_LEN],
+}
+
+impl TraceDump {
+    pub fn new() -> Self {
+        TraceDump {
+            dropped: 0,
+            len: 0,
+            entries: [TraceEntry::default(); TRACE_BUF_LEN],
+        }
+    }
+
+    /// The recorded entries, oldest first.
+    pub fn entries(&self) -> &[TraceEntry] {
+        &self.entries[..self.len]
+    }
+}
+
+impl Default for TraceDump {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct Stat {
@@ -307,6 +580,348 @@ pub fn task_info(info: &TaskInfo) -> isize {
     sys_task_info(info)
 }
 
+pub fn swap_priorities(id_a: usize, id_b: usize) -> isize {
+    sys_swap_priorities(id_a, id_b)
+}
+
+pub fn get_quantum_time() -> isize {
+    sys_get_quantum_time()
+}
+
+pub fn set_panic_policy(policy: usize) -> isize {
+    sys_set_panic_policy(policy)
+}
+
+pub fn snapshot_syscalls(snapshot: &mut [u32; MAX_SYSCALL_NUM]) -> isize {
+    sys_snapshot_syscalls(snapshot)
+}
+
+/// Per-syscall counts made between two snapshots, saturating at zero.
+pub fn diff_snapshots(
+    before: &[u32; MAX_SYSCALL_NUM],
+    after: &[u32; MAX_SYSCALL_NUM],
+) -> [u32; MAX_SYSCALL_NUM] {
+    let mut delta = [0; MAX_SYSCALL_NUM];
+    for (i, d) in delta.iter_mut().enumerate() {
+        *d = after[i].saturating_sub(before[i]);
+    }
+    delta
+}
+
+pub fn set_task_name(name: &str) -> isize {
+    sys_set_task_name(name)
+}
+
+pub fn export_tasks_csv(buf: &mut [u8]) -> isize {
+    sys_export_tasks_csv(buf)
+}
+
+pub fn get_remaining_slice() -> isize {
+    sys_get_remaining_slice()
+}
+
+pub fn get_last_exited(time_us: &mut usize) -> isize {
+    sys_get_last_exited(time_us)
+}
+
+pub fn barrier_init(id: usize, count: usize) -> isize {
+    sys_barrier_init(id, count)
+}
+
+pub fn barrier_wait(id: usize) -> isize {
+    sys_barrier_wait(id)
+}
+
+pub fn set_trace_enabled(id: usize, enabled: bool) -> isize {
+    sys_set_trace_enabled(id, enabled)
+}
+
+pub fn dump_trace(id: usize, dump: &mut TraceDump) -> isize {
+    sys_dump_trace(id, dump)
+}
+
+pub fn get_run_time_stddev() -> isize {
+    sys_get_run_time_stddev()
+}
+
+pub fn set_fallback_task(id: usize) -> isize {
+    sys_set_fallback_task(id)
+}
+
+pub fn get_current_task_brief() -> (usize, TaskStatus) {
+    let brief = sys_get_task_brief() as usize;
+    let status = match brief & 0xff {
+        1 => TaskStatus::Ready,
+        2 => TaskStatus::Running,
+        3 => TaskStatus::Exited,
+        4 => TaskStatus::Blocked,
+        5 => TaskStatus::Sleeping,
+        6 => TaskStatus::Frozen,
+        _ => TaskStatus::UnInit,
+    };
+    (brief >> 8, status)
+}
+
+pub fn set_syscall_validation(enabled: bool) -> isize {
+    sys_set_syscall_validation(enabled)
+}
+
+pub fn set_account_self_overhead(enabled: bool) -> isize {
+    sys_set_account_self_overhead(enabled)
+}
+
+pub fn get_recent_switch_latency(latency: &mut SwitchLatency) -> isize {
+    sys_get_recent_switch_latency(latency)
+}
+
+pub fn switch_to(id: usize) -> isize {
+    sys_switch_to(id)
+}
+
+pub fn get_init_duration() -> isize {
+    sys_get_init_duration()
+}
+
+pub fn peek_next_task(current: &mut usize) -> isize {
+    sys_peek_next_task(current)
+}
+
+pub fn record_schedule() -> isize {
+    sys_record_schedule()
+}
+
+pub fn stop_recording(buf: &mut [usize]) -> isize {
+    sys_stop_recording(buf)
+}
+
+pub fn replay_schedule(schedule: &[usize]) -> isize {
+    sys_replay_schedule(schedule)
+}
+
+pub fn is_system_idle() -> bool {
+    sys_is_system_idle() == 1
+}
+
+pub fn set_max_switches(n: usize) -> isize {
+    sys_set_max_switches(n)
+}
+
+pub fn get_priority_inversions() -> isize {
+    sys_get_priority_inversions()
+}
+
+pub fn set_min_share(id: usize, permille: usize) -> isize {
+    sys_set_min_share(id, permille)
+}
+
+pub fn dump_task_table(buf: &mut [TaskDumpEntry]) -> isize {
+    sys_dump_task_table(buf)
+}
+
+pub fn get_system_syscall_times(buf: &mut [u64; MAX_SYSCALL_NUM]) -> isize {
+    sys_get_system_syscall_times(buf)
+}
+
+pub fn uptime_ticks() -> isize {
+    sys_uptime_ticks()
+}
+
+pub fn dump_switch_trace(dump: &mut SwitchTraceDump) -> isize {
+    sys_dump_switch_trace(dump)
+}
+
+pub fn reset_syscall_stats() -> isize {
+    sys_reset_syscall_stats()
+}
+
+pub fn get_idle_time() -> isize {
+    sys_get_idle_time()
+}
+
+pub fn time_since(epoch_us: usize) -> isize {
+    sys_time_since(epoch_us)
+}
+
+pub fn set_cpu_budget(id: usize, us: usize) -> isize {
+    sys_set_cpu_budget(id, us)
+}
+
+pub fn get_dispatch_coverage(total: &mut usize) -> isize {
+    sys_get_dispatch_coverage(total)
+}
+
+pub fn set_time_slice(id: usize, ms: usize) -> isize {
+    sys_set_time_slice(id, ms)
+}
+
+pub fn restart_task(id: usize) -> isize {
+    sys_restart_task(id)
+}
+
+pub fn get_prev_run_info(id: usize, info: &mut TaskInfoSummary) -> isize {
+    sys_get_prev_run_info(id, info)
+}
+
+pub fn waittask(id: usize, timeout_ms: usize) -> isize {
+    sys_waittask(id, timeout_ms)
+}
+
+pub fn list_tasks_by_status(status: TaskStatus, buf: &mut [usize]) -> isize {
+    sys_list_tasks_by_status(status, buf)
+}
+
+pub fn sleep_us(us: usize) -> isize {
+    sys_sleep_us(us)
+}
+
+pub fn priority_boost_begin() -> isize {
+    sys_priority_boost_begin()
+}
+
+pub fn priority_boost_end() -> isize {
+    sys_priority_boost_end()
+}
+
+pub fn get_lru_task() -> isize {
+    sys_get_lru_task()
+}
+
+pub fn get_sched_overhead_permille() -> isize {
+    sys_get_sched_overhead()
+}
+
+pub fn state_fingerprint() -> u64 {
+    sys_state_fingerprint() as u64
+}
+
+pub fn set_deadline(ms: usize) -> isize {
+    sys_set_deadline(ms)
+}
+
+pub fn total_system_syscalls() -> u64 {
+    sys_total_system_syscalls() as u64
+}
+
+pub fn soft_reset() -> ! {
+    sys_soft_reset()
+}
+
+pub fn get_slot_idle(id: usize) -> isize {
+    sys_get_slot_idle(id)
+}
+
+pub fn get_dispatch_balance_permille() -> isize {
+    sys_get_dispatch_balance()
+}
+
+pub fn next_wake_time_us() -> isize {
+    sys_next_wake_time()
+}
+
+pub fn get_kernel_entries() -> isize {
+    sys_get_kernel_entries()
+}
+
+pub fn get_task_record(id: usize, buf: &mut [u8]) -> isize {
+    sys_get_task_record(id, buf)
+}
+
+pub fn set_syscall_storm_threshold(per_sec: usize) -> isize {
+    sys_set_syscall_storm_threshold(per_sec)
+}
+
+pub fn list_uninit_slots(buf: &mut [usize]) -> isize {
+    sys_list_uninit_slots(buf)
+}
+
+pub fn stack_high_water(id: usize) -> isize {
+    sys_stack_high_water(id)
+}
+
+pub fn set_count_failures(enabled: bool) -> isize {
+    sys_set_count_failures(enabled)
+}
+
+pub fn list_related(id: usize, buf: &mut [usize]) -> isize {
+    sys_list_related(id, buf)
+}
+
+pub fn set_kernel_residency_warn(us: usize) -> isize {
+    sys_set_kernel_residency_warn(us)
+}
+
+pub fn get_busiest_task() -> isize {
+    sys_get_busiest_task()
+}
+
+pub fn task_info_by_id(id: usize, info: &TaskInfo) -> isize {
+    sys_task_info_by_id(id, info)
+}
+
+pub fn set_preemption(enabled: bool) -> isize {
+    sys_set_preemption(enabled)
+}
+
+pub fn extend_budget(id: usize, ms: usize) -> isize {
+    sys_extend_budget(id, ms)
+}
+
+pub fn get_time_breakdown(tb: &mut TimeBreakdown) -> isize {
+    sys_get_time_breakdown(tb)
+}
+
+pub fn set_counting(enabled: bool) -> isize {
+    sys_set_counting(enabled)
+}
+
+pub fn get_cpu_gini_permille() -> isize {
+    sys_get_cpu_gini()
+}
+
+pub fn get_selection_stats(calls: &mut usize) -> isize {
+    sys_get_selection_stats(calls)
+}
+
+pub fn get_task_statuses(buf: &mut [u8]) -> isize {
+    sys_get_task_statuses(buf)
+}
+
+pub fn get_max_idle_stretch() -> isize {
+    sys_get_max_idle_stretch()
+}
+
+pub fn inject_fault(kind: TrapCause) -> isize {
+    sys_inject_fault(kind as usize)
+}
+
+pub fn set_global_quantum(ms: usize) -> isize {
+    sys_set_global_quantum(ms)
+}
+
+pub fn get_global_quantum() -> isize {
+    sys_get_global_quantum()
+}
+
+pub fn get_time_anomalies() -> isize {
+    sys_get_time_anomalies()
+}
+
+pub fn sleep_until(abs_us: usize) -> isize {
+    sys_sleep_until(abs_us)
+}
+
+pub fn predict_schedule(buf: &mut [usize]) -> isize {
+    sys_predict_schedule(buf)
+}
+
+pub fn set_auto_freeze_threshold(ms: usize) -> isize {
+    sys_set_auto_freeze_threshold(ms)
+}
+
+pub fn task_stats(id: usize, stats: &TaskStats) -> isize {
+    sys_task_stats(id, stats)
+}
+
 pub fn thread_create(entry: usize, arg: usize) -> isize {
     sys_thread_create(entry, arg)
 }